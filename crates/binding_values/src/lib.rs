@@ -53,6 +53,7 @@ pub use normal_module_factory::*;
 pub use options::*;
 pub use path_data::*;
 pub use plugins::buildtime_plugins;
+pub use plugins::get_builtin_loader;
 pub(crate) use plugins::*;
 pub use raw_options::*;
 pub use resolver::*;
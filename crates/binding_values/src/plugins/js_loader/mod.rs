@@ -5,6 +5,7 @@ mod scheduler;
 use std::fmt::Debug;
 
 pub use context::JsLoaderContext;
+pub use resolver::get_builtin_loader;
 use napi::bindgen_prelude::*;
 use rspack_core::{ApplyContext, CompilerOptions, Plugin, PluginContext};
 use rspack_error::Result;
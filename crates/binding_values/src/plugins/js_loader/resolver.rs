@@ -3,6 +3,8 @@ use std::{
   sync::{Arc, LazyLock},
 };
 
+use loader_barrel::{BarrelLoader, BARREL_LOADER_IDENTIFIER};
+use loader_compilation::{CompilationLoader, COMPILATION_LOADER_IDENTIFIER};
 use rspack_cacheable::{cacheable, cacheable_dyn};
 use rspack_collections::{Identifiable, Identifier};
 use rspack_core::{
@@ -19,7 +21,6 @@ use rspack_loader_lightningcss::{config::Config, LIGHTNINGCSS_LOADER_IDENTIFIER}
 use rspack_loader_preact_refresh::PREACT_REFRESH_LOADER_IDENTIFIER;
 use rspack_loader_react_refresh::REACT_REFRESH_LOADER_IDENTIFIER;
 use rspack_loader_swc::{SwcLoader, SWC_LOADER_IDENTIFIER};
-use loader_compilation::{CompilationLoader, COMPILATION_LOADER_IDENTIFIER};
 use rspack_paths::Utf8Path;
 use rustc_hash::FxHashMap;
 use tokio::sync::RwLock;
@@ -51,8 +52,13 @@ pub fn serde_error_to_miette(
 
 type SwcLoaderCache<'a> = LazyLock<RwLock<FxHashMap<(Cow<'a, str>, Arc<str>), Arc<SwcLoader>>>>;
 static SWC_LOADER_CACHE: SwcLoaderCache = LazyLock::new(|| RwLock::new(FxHashMap::default()));
-type CompilationLoaderCache<'a> = LazyLock<RwLock<FxHashMap<(Cow<'a, str>, Arc<str>), Arc<CompilationLoader>>>>;
-static COMPILATION_LOADER_CACHE: CompilationLoaderCache = LazyLock::new(|| RwLock::new(FxHashMap::default()));
+type CompilationLoaderCache<'a> =
+  LazyLock<RwLock<FxHashMap<(Cow<'a, str>, Arc<str>), Arc<CompilationLoader>>>>;
+static COMPILATION_LOADER_CACHE: CompilationLoaderCache =
+  LazyLock::new(|| RwLock::new(FxHashMap::default()));
+type BarrelLoaderCache<'a> =
+  LazyLock<RwLock<FxHashMap<(Cow<'a, str>, Arc<str>), Arc<BarrelLoader>>>>;
+static BARREL_LOADER_CACHE: BarrelLoaderCache = LazyLock::new(|| RwLock::new(FxHashMap::default()));
 
 pub async fn get_builtin_loader(builtin: &str, options: Option<&str>) -> Result<BoxLoader> {
   let options: Arc<str> = options.unwrap_or("{}").into();
@@ -93,13 +99,18 @@ pub async fn get_builtin_loader(builtin: &str, options: Option<&str>) -> Result<
     {
       return Ok(loader.clone());
     }
-    let loader = Arc::new(loader_compilation::CompilationLoader::new(options.as_ref())
-      .map_err(|e| {
-        serde_error_to_miette(e, options.clone(), "falied to parse builtin:compilation-loader options")
-      })?
-      .with_identifier(builtin.into()),
+    let loader = Arc::new(
+      loader_compilation::CompilationLoader::new(options.as_ref())
+        .map_err(|e| {
+          serde_error_to_miette(
+            e,
+            options.clone(),
+            "falied to parse builtin:compilation-loader options",
+          )
+        })?
+        .with_identifier(builtin.into()),
     );
-    
+
     COMPILATION_LOADER_CACHE.write().await.insert(
       (Cow::Owned(builtin.to_owned()), options.clone()),
       loader.clone(),
@@ -107,6 +118,34 @@ pub async fn get_builtin_loader(builtin: &str, options: Option<&str>) -> Result<
     return Ok(loader);
   }
 
+  // Customize barrel loader.
+  if builtin.starts_with(BARREL_LOADER_IDENTIFIER) {
+    if let Some(loader) = BARREL_LOADER_CACHE
+      .read()
+      .await
+      .get(&(Cow::Borrowed(builtin), options.clone()))
+    {
+      return Ok(loader.clone());
+    }
+    let loader = Arc::new(
+      loader_barrel::BarrelLoader::new(options.as_ref())
+        .map_err(|e| {
+          serde_error_to_miette(
+            e,
+            options.clone(),
+            "failed to parse builtin:barrel-loader options",
+          )
+        })?
+        .with_identifier(builtin.into()),
+    );
+
+    BARREL_LOADER_CACHE.write().await.insert(
+      (Cow::Owned(builtin.to_owned()), options.clone()),
+      loader.clone(),
+    );
+    return Ok(loader);
+  }
+
   if builtin.starts_with(LIGHTNINGCSS_LOADER_IDENTIFIER) {
     let config: rspack_loader_lightningcss::config::RawConfig =
       serde_json::from_str(options.as_ref()).map_err(|e| {
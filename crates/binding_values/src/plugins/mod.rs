@@ -2,5 +2,6 @@ mod context_replacement;
 mod js_loader;
 
 pub use context_replacement::*;
+pub use js_loader::get_builtin_loader;
 pub(super) use js_loader::{JsLoaderRspackPlugin, JsLoaderRunner};
 pub mod buildtime_plugins;
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
 
+use loader_barrel::{collect_barrel_reports, BarrelReport, BARREL_REPORT_META_KEY};
 use napi_derive::napi;
 use rspack_collections::Identifier;
 use rspack_core::{
@@ -931,6 +932,7 @@ impl From<rspack_core::StatsAssetsByChunkName> for JsStatsAssetsByChunkName {
 #[napi(object, object_to_js = false)]
 pub struct JsStatsOptions {
   pub assets: bool,
+  pub barrels: bool,
   pub cached_modules: bool,
   pub chunks: bool,
   pub chunk_group_auxiliary: bool,
@@ -994,10 +996,34 @@ pub struct JsStatsGetAssets {
   pub assets_by_chunk_name: Vec<JsStatsAssetsByChunkName>,
 }
 
+#[napi(object, object_from_js = false)]
+pub struct JsStatsBarrel {
+  pub resource: String,
+  pub names: Vec<String>,
+  pub targets: Vec<String>,
+  pub export_count: u32,
+  pub wildcard_depth: u32,
+  pub fallback: bool,
+}
+
+impl From<BarrelReport> for JsStatsBarrel {
+  fn from(report: BarrelReport) -> Self {
+    Self {
+      resource: report.resource,
+      names: report.names,
+      targets: report.targets,
+      export_count: report.export_count,
+      wildcard_depth: report.wildcard_depth,
+      fallback: report.fallback,
+    }
+  }
+}
+
 #[napi(object, object_from_js = false)]
 pub struct JsStatsCompilation {
   pub assets: Option<Vec<JsStatsAsset>>,
   pub assets_by_chunk_name: Option<Vec<JsStatsAssetsByChunkName>>,
+  pub barrels: Option<Vec<JsStatsBarrel>>,
   pub chunks: Option<Vec<JsStatsChunk>>,
   pub entrypoints: Option<Vec<JsStatsChunkGroup>>,
   pub errors: Vec<JsStatsError>,
@@ -1042,6 +1068,8 @@ impl JsStats {
 impl JsStats {
   #[napi(ts_return_type = "JsStatsCompilation")]
   pub fn to_json(&self, js_options: JsStatsOptions) -> Result<JsStatsCompilationWrapper> {
+    let barrels = js_options.barrels.then(|| self.barrels());
+
     let options = ExtendedStatsOptions::from(js_options);
 
     let hash = options.hash.then(|| self.hash()).flatten();
@@ -1085,6 +1113,7 @@ impl JsStats {
     Ok(JsStatsCompilationWrapper(JsStatsCompilation {
       assets,
       assets_by_chunk_name,
+      barrels,
       chunks,
       entrypoints,
       errors,
@@ -1167,6 +1196,21 @@ impl JsStats {
       .collect()
   }
 
+  fn barrels(&self) -> Vec<JsStatsBarrel> {
+    let module_graph = self.inner.compilation.get_module_graph();
+    let reports = module_graph.modules().into_values().filter_map(|module| {
+      module
+        .build_info()
+        .parse_meta
+        .get(BARREL_REPORT_META_KEY)
+        .map(String::as_str)
+    });
+    collect_barrel_reports(reports)
+      .into_iter()
+      .map(Into::into)
+      .collect()
+  }
+
   #[napi]
   pub fn has_warnings(&self) -> bool {
     !self.inner.get_warnings().is_empty()
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use binding_values::get_builtin_loader;
+use loader_barrel::BARREL_LOADER_IDENTIFIER;
+use rspack_collections::Identifiable;
+
+#[tokio::test]
+async fn test_barrel_loader_from_js_options() {
+  let options = r#"{"names":["Button","Input"]}"#;
+  let loader = get_builtin_loader(BARREL_LOADER_IDENTIFIER, Some(options))
+    .await
+    .unwrap();
+  assert_eq!(loader.identifier().as_str(), BARREL_LOADER_IDENTIFIER);
+
+  let cached = get_builtin_loader(BARREL_LOADER_IDENTIFIER, Some(options))
+    .await
+    .unwrap();
+  assert!(Arc::ptr_eq(&loader, &cached));
+
+  let other = get_builtin_loader(BARREL_LOADER_IDENTIFIER, Some(r#"{"names":["Button"]}"#))
+    .await
+    .unwrap();
+  assert!(!Arc::ptr_eq(&loader, &other));
+}
+
+#[tokio::test]
+async fn test_barrel_loader_invalid_options() {
+  let result = get_builtin_loader(BARREL_LOADER_IDENTIFIER, Some(r#"{"names":"Button"}"#)).await;
+  assert!(result.is_err());
+}
@@ -0,0 +1,374 @@
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+};
+
+use rspack_core::{
+  cache::persistent::storage::{create_storage, StorageOptions},
+  CompilerOptions, DependencyCategory, ExperimentCacheOptions, ResolveResult, Resolver,
+};
+use rspack_fs::IntermediateFileSystem;
+use rspack_storage::Storage;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::TransformMapping;
+
+// Maximum number of barrel files whose mappings are kept in memory.
+const MEMORY_CACHE_CAPACITY: usize = 4096;
+
+/// Mapping caches of a compiler, owned by [crate::BarrelLoaderPlugin].
+#[derive(Debug)]
+pub(crate) struct BarrelMappingCaches {
+  pub memory: MemoryCache<TransformMapping>,
+  pub persistent: Option<PersistentMappingCache>,
+}
+
+impl BarrelMappingCaches {
+  pub(crate) fn new(
+    compiler_options: &CompilerOptions,
+    intermediate_fs: Option<Arc<dyn IntermediateFileSystem>>,
+  ) -> Self {
+    Self {
+      memory: MemoryCache::new(MEMORY_CACHE_CAPACITY),
+      persistent: intermediate_fs
+        .and_then(|fs| PersistentMappingCache::from_compiler_options(compiler_options, fs)),
+    }
+  }
+
+  pub(crate) async fn get(
+    &self,
+    resolver: &Resolver,
+    file: &Path,
+    source: &str,
+  ) -> Option<TransformMapping> {
+    let hash = content_hash(source.as_bytes());
+    if let Some(mapping) = self.memory.get(file, hash) {
+      return Some(mapping);
+    }
+    let mapping = self
+      .persistent
+      .as_ref()?
+      .get(file, source, |context, request| {
+        resolve_path(resolver, context, request)
+      })
+      .await?;
+    self.memory.insert(
+      file.to_path_buf(),
+      hash,
+      mapping.clone(),
+      mapping.files.clone(),
+    );
+    Some(mapping)
+  }
+
+  pub(crate) async fn set(&self, file: &Path, source: &str, mapping: &TransformMapping) {
+    if let Some(persistent) = &self.persistent {
+      persistent.set(file, source, mapping).await;
+    }
+    self.memory.insert(
+      file.to_path_buf(),
+      content_hash(source.as_bytes()),
+      mapping.clone(),
+      mapping.files.clone(),
+    );
+  }
+
+  /// Save the mappings computed by the compilation, if any.
+  pub(crate) async fn save(&self) {
+    if let Some(persistent) = &self.persistent {
+      persistent.save().await;
+    }
+  }
+}
+
+pub fn content_hash(bytes: &[u8]) -> u64 {
+  xxhash_rust::xxh3::xxh3_64(bytes)
+}
+
+#[derive(Debug)]
+struct MemoryCacheEntry<T> {
+  // Content hash of the file the value is computed from.
+  hash: u64,
+  value: T,
+  // Other files the value depends on.
+  files: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+struct MemoryCacheState<T> {
+  entries: HashMap<PathBuf, MemoryCacheEntry<T>>,
+  // Keys in insertion order, the oldest entries are evicted first.
+  order: VecDeque<PathBuf>,
+}
+
+/// Values computed from a file and the files it depends on, such as the files reached
+/// through `export *`.
+///
+/// An entry is only used while the content hash of the file matches, and is evicted once
+/// any of its files is modified, see [MemoryCache::invalidate].
+#[derive(Debug)]
+pub struct MemoryCache<T> {
+  capacity: usize,
+  state: Mutex<MemoryCacheState<T>>,
+}
+
+impl<T: Clone> MemoryCache<T> {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      state: Mutex::new(MemoryCacheState {
+        entries: HashMap::new(),
+        order: VecDeque::new(),
+      }),
+    }
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, MemoryCacheState<T>> {
+    self.state.lock().expect("failed to lock memory cache")
+  }
+
+  /// Get the value of `key` computed from the content with `hash`.
+  pub fn get(&self, key: &Path, hash: u64) -> Option<T> {
+    self
+      .lock()
+      .entries
+      .get(key)
+      .filter(|entry| entry.hash == hash)
+      .map(|entry| entry.value.clone())
+  }
+
+  /// Insert the value of `key` computed from the content with `hash` and from `files`.
+  pub fn insert(&self, key: PathBuf, hash: u64, value: T, files: Vec<PathBuf>) {
+    let mut state = self.lock();
+    let entry = MemoryCacheEntry { hash, value, files };
+    if state.entries.insert(key.clone(), entry).is_none() {
+      state.order.push_back(key);
+    }
+    while state.entries.len() > self.capacity {
+      let Some(oldest) = state.order.pop_front() else {
+        break;
+      };
+      state.entries.remove(&oldest);
+    }
+  }
+
+  /// Evict the entries computed from any of the modified `files`.
+  pub fn invalidate<'a>(&self, files: impl IntoIterator<Item = &'a Path>) {
+    let files = files.into_iter().collect::<HashSet<_>>();
+    if files.is_empty() {
+      return;
+    }
+    let mut state = self.lock();
+    let MemoryCacheState { entries, order } = &mut *state;
+    entries.retain(|_, entry| {
+      !entry
+        .files
+        .iter()
+        .any(|file| files.contains(file.as_path()))
+    });
+    order.retain(|key| entries.contains_key(key));
+  }
+
+  pub fn len(&self) -> usize {
+    self.lock().entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+/// A re-export request resolved while computing a mapping, either an `export *` request
+/// or the source of named re-exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WildcardResolution {
+  pub context: PathBuf,
+  pub request: String,
+  pub resolved: Option<PathBuf>,
+}
+
+impl WildcardResolution {
+  pub fn new(context: &Path, request: &str, result: &ResolveResult) -> Self {
+    let resolved = match result {
+      ResolveResult::Resource(resource) => Some(resource.path.as_std_path().to_path_buf()),
+      ResolveResult::Ignored => None,
+    };
+    Self {
+      context: context.to_path_buf(),
+      request: request.to_string(),
+      resolved,
+    }
+  }
+}
+
+fn resolve_path(resolver: &Resolver, context: &Path, request: &str) -> Option<PathBuf> {
+  match resolver.resolve(context, request) {
+    Ok(ResolveResult::Resource(resource)) => Some(resource.path.as_std_path().to_path_buf()),
+    _ => None,
+  }
+}
+
+const MAPPING_SCOPE: &str = "barrel_mapping";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistentEntry {
+  mapping: TransformMapping,
+  // Content hash of every file in `mapping.files`.
+  hashes: Vec<u64>,
+}
+
+/// Barrel export maps stored next to the persistent cache of the compiler,
+/// so that warm builds can skip barrel analysis.
+///
+/// Entries are keyed by the barrel file, its content and the resolver options,
+/// and are only used while the files reached through `export *` are unchanged.
+#[derive(Debug)]
+pub struct PersistentMappingCache {
+  storage: Arc<dyn Storage>,
+  resolve_options_hash: u64,
+  // Entries loaded from the storage, keyed by `entry_key`.
+  entries: OnceCell<HashMap<Vec<u8>, Arc<Vec<u8>>>>,
+  // Whether entries were set since the last save.
+  dirty: AtomicBool,
+}
+
+impl PersistentMappingCache {
+  pub fn new(storage: Arc<dyn Storage>, resolve_options_hash: u64) -> Self {
+    Self {
+      storage,
+      resolve_options_hash,
+      entries: OnceCell::new(),
+      dirty: AtomicBool::new(false),
+    }
+  }
+
+  fn from_compiler_options(
+    compiler_options: &CompilerOptions,
+    intermediate_fs: Arc<dyn IntermediateFileSystem>,
+  ) -> Option<Self> {
+    let ExperimentCacheOptions::Persistent(persistent) = &compiler_options.experiments.cache else {
+      return None;
+    };
+    let StorageOptions::FileSystem { directory } = &persistent.storage;
+    let storage = create_storage(
+      StorageOptions::FileSystem {
+        directory: directory.join("barrel"),
+      },
+      persistent.version.clone(),
+      intermediate_fs,
+    );
+    Some(Self::new(storage, resolve_options_hash(compiler_options)))
+  }
+
+  fn entry_key(&self, file: &Path, source: &str) -> Vec<u8> {
+    let mut hasher = Xxh3::new();
+    hasher.update(&self.resolve_options_hash.to_le_bytes());
+    hasher.update(file.to_string_lossy().as_bytes());
+    hasher.update(&[0]);
+    hasher.update(&content_hash(source.as_bytes()).to_le_bytes());
+    hasher.digest().to_le_bytes().to_vec()
+  }
+
+  async fn entries(&self) -> &HashMap<Vec<u8>, Arc<Vec<u8>>> {
+    self
+      .entries
+      .get_or_init(|| async {
+        // Failing to load the cache should never fail the build.
+        self
+          .storage
+          .load(MAPPING_SCOPE)
+          .await
+          .unwrap_or_default()
+          .into_iter()
+          .map(|(key, value)| (key.as_ref().clone(), value))
+          .collect()
+      })
+      .await
+  }
+
+  /// Get the mapping of `file` with `source`, `resolve` resolves an `export *` request
+  /// with the current resolver.
+  pub async fn get(
+    &self,
+    file: &Path,
+    source: &str,
+    resolve: impl Fn(&Path, &str) -> Option<PathBuf>,
+  ) -> Option<TransformMapping> {
+    let content = self.entries().await.get(&self.entry_key(file, source))?;
+    let entry: PersistentEntry = serde_json::from_slice(content).ok()?;
+    if entry.mapping.files.first().map(PathBuf::as_path) != Some(file)
+      || entry.mapping.files.len() != entry.hashes.len()
+    {
+      return None;
+    }
+    // `export *` requests must still resolve to the same files, a file may have been
+    // added in front of the previous one.
+    if entry
+      .mapping
+      .resolutions
+      .iter()
+      .any(|resolution| resolve(&resolution.context, &resolution.request) != resolution.resolved)
+    {
+      return None;
+    }
+    // The barrel file itself is covered by the entry key,
+    // check the files reached through `export *`.
+    for (file, hash) in entry.mapping.files.iter().zip(&entry.hashes).skip(1) {
+      let content = tokio::fs::read(file).await.ok()?;
+      if content_hash(&content) != *hash {
+        return None;
+      }
+    }
+    Some(entry.mapping)
+  }
+
+  pub async fn set(&self, file: &Path, source: &str, mapping: &TransformMapping) {
+    let mut hashes = vec![content_hash(source.as_bytes())];
+    for file in mapping.files.iter().skip(1) {
+      let Ok(content) = tokio::fs::read(file).await else {
+        return;
+      };
+      hashes.push(content_hash(&content));
+    }
+    let entry = PersistentEntry {
+      mapping: mapping.clone(),
+      hashes,
+    };
+    let Ok(content) = serde_json::to_vec(&entry) else {
+      return;
+    };
+    self
+      .storage
+      .set(MAPPING_SCOPE, self.entry_key(file, source), content);
+    self.dirty.store(true, Ordering::Relaxed);
+  }
+
+  /// Write the entries set since the last save to the storage.
+  pub async fn save(&self) {
+    if !self.dirty.swap(false, Ordering::Relaxed) {
+      return;
+    }
+    // Failing to save the cache should never fail the build.
+    if let Ok(receiver) = self.storage.trigger_save() {
+      let _ = receiver.await;
+    }
+  }
+}
+
+// Barrel files are resolved as ESM, see `BarrelLoader::loader_impl`.
+fn resolve_options_hash(compiler_options: &CompilerOptions) -> u64 {
+  let mut resolve = compiler_options
+    .resolve
+    .clone()
+    .merge_by_dependency(DependencyCategory::Esm);
+  // The options by dependency are merged above, and the remaining options hold no hash maps,
+  // so that the debug output is stable across builds.
+  resolve.by_dependency = None;
+  content_hash(format!("{resolve:?}").as_bytes())
+}
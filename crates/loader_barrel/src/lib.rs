@@ -3,41 +3,57 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::{
-  collections::{HashMap, HashSet},
-  path::PathBuf,
+  collections::{BTreeSet, HashMap, HashSet},
+  path::{Path, PathBuf},
   sync::Arc,
 };
 
-use lazy_static::lazy_static;
-use regex::Regex;
 use rspack_core::{
-  DependencyCategory, RunnerContext, ResolveOptionsWithDependencyType, ResolveResult,
+  rspack_sources::SourceMap, DependencyCategory, RunnerContext, ResolveOptionsWithDependencyType, ResolveResult,
   Resolver,
 };
 use rspack_error::{error, AnyhowError, Result};
-use rspack_cacheable::{cacheable, cacheable_dyn, with::{AsRefStrConverter, AsRefStr}};
+use rspack_cacheable::{cacheable, cacheable_dyn, with::{AsRefStrConverter, AsRefStr, Skip}};
 use rspack_loader_runner::{Content, Identifiable, Identifier, Loader, LoaderContext};
 use rspack_plugin_javascript::{
   ast::{self, SourceMapConfig},
   TransformOutput,
 };
-use serde::Deserialize;
+use rspack_util::source_map::SourceMapKind;
+use serde::{Deserialize, Serialize};
 use swc_compiler::{IntoJsAst, SwcCompiler};
 use swc_core::{
-  base::config::{Options, OutputCharset},
+  base::{
+    config::{Options, OutputCharset},
+    sourcemap::SourceMapBuilder,
+  },
   ecma::{
-    ast::EsVersion,
+    ast::{noop_pass, EsVersion},
     parser::{Syntax, TsSyntax},
   },
 };
-use swc_optimize_barrel::optimize_barrel;
-use tokio::sync::Mutex;
+use swc_keep_export::{keep_residual_exports, ExportPattern};
+use swc_optimize_barrel::{analyze_barrel, BarrelAnalysis, ExportEntry};
+
+mod cache;
+use cache::BarrelMappingCaches;
+pub use cache::{content_hash, MemoryCache, PersistentMappingCache, WildcardResolution};
+mod plugin;
+pub use plugin::BarrelLoaderPlugin;
+mod report;
+pub use report::{collect_barrel_reports, BarrelReport, BARREL_REPORT_META_KEY};
+use report::record_barrel_report;
 
 pub const BARREL_LOADER_IDENTIFIER: &str = "builtin:barrel-loader";
 
+// Resource query of the module which keeps the local declarations of a partial barrel file.
+const BARREL_RESIDUAL_QUERY: &str = "__barrel_residual__";
+
 #[cacheable(with=AsRefStr)]
 #[derive(Debug, Deserialize)]
 pub struct LoaderOptions {
+  // Raw options string, used to restore the loader from the persistent cache.
+  #[serde(skip)]
   options: String,
   pub names: Vec<String>,
   pub cache_dir: Option<String>,
@@ -57,7 +73,9 @@ impl TryFrom<&str> for LoaderOptions {
   type Error = serde_json::Error;
 
   fn try_from(s: &str) -> Result<Self, Self::Error> {
-    serde_json::from_str(s)
+    let mut options: LoaderOptions = serde_json::from_str(s)?;
+    options.options = s.into();
+    Ok(options)
   }
 }
 
@@ -66,6 +84,9 @@ impl TryFrom<&str> for LoaderOptions {
 pub struct BarrelLoader {
   identifier: Identifier,
   loader_options: LoaderOptions,
+  // Caches of the compiler, missing when the loader is not created by `BarrelLoaderPlugin`.
+  #[cacheable(with=Skip)]
+  caches: Option<Arc<BarrelMappingCaches>>,
 }
 
 impl BarrelLoader {
@@ -74,9 +95,15 @@ impl BarrelLoader {
     Ok(Self {
       identifier: BARREL_LOADER_IDENTIFIER.into(),
       loader_options,
+      caches: None,
     })
   }
 
+  pub(crate) fn with_caches(mut self, caches: Arc<BarrelMappingCaches>) -> Self {
+    self.caches = Some(caches);
+    self
+  }
+
   pub fn with_identifier(mut self, identifier: Identifier) -> Self {
     assert!(identifier.starts_with(BARREL_LOADER_IDENTIFIER));
     self.identifier = identifier;
@@ -104,27 +131,44 @@ impl BarrelLoader {
 
     let source = content.try_into_string()?;
 
-    let result = {
-      // Get barrel mapping
-      let mut mapping_result = GLOBAL_TRANSFORM_MAPPING.lock().await;
-      let resource_key = resource_path.as_str();
-      if mapping_result.contains_key(resource_key) {
-        mapping_result.get(resource_key).cloned()
-      } else {
-        let visited = HashSet::new();
-        let ret = get_barrel_map(
-          visited,
-          resolver,
-          resource_path.clone().into_std_path_buf(),
+    if loader_context
+      .resource_query()
+      .is_some_and(|query| query.contains(BARREL_RESIDUAL_QUERY))
+    {
+      let TransformOutput { code, map } = get_residual_module(
+        resource_path.as_std_path(),
+        source,
+        &self.loader_options.names,
+        self.loader_options.cache_dir.clone(),
+        loader_context.context.module_source_map_kind,
+      )?;
+      loader_context.finish_with((code, map));
+      return Ok(());
+    }
+
+    let resource_key = resource_path.clone().into_std_path_buf();
+    let cached = match &self.caches {
+      Some(caches) => caches.get(&resolver, &resource_key, &source).await,
+      None => None,
+    };
+    let result = match cached {
+      Some(mapping) => Some(mapping),
+      None => {
+        let mapping = get_barrel_map(
+          HashSet::new(),
+          resolver.clone(),
+          resource_key.clone(),
           self.loader_options.cache_dir.clone(),
           false,
-          Some(source),
+          Some(source.clone()),
         )
         .await?;
-        if let Some(mapping) = ret.clone() {
-          mapping_result.insert(resource_key.to_string(), mapping);
+        if let Some(caches) = &self.caches
+          && let Some(mapping) = &mapping
+        {
+          caches.set(&resource_key, &source, mapping).await;
         }
-        ret
+        mapping
       }
     };
     let mut export_map = HashMap::new();
@@ -132,12 +176,18 @@ impl BarrelLoader {
       export_list,
       wildcard_exports,
       is_client_entry,
+      files,
+      locations,
+      wildcard_depth,
+      side_effects,
+      resolutions,
     }) = result
     {
-      export_list.iter().for_each(|list| {
-        let key = list[0].clone();
-        let value = (list[1].clone(), list[2].clone());
-        export_map.insert(key, value);
+      // Files reached through `export *` affect the export list of the barrel file.
+      loader_context.file_dependencies.extend(files);
+
+      export_list.iter().for_each(|entry| {
+        export_map.insert(entry.name.clone(), (entry.src.clone(), entry.orig.clone()));
       });
 
       let names = &self.loader_options.names;
@@ -147,10 +197,40 @@ impl BarrelLoader {
       } else {
         String::from("")
       };
+      // Local exports of a partial barrel file are served by the residual module.
+      let local_names = names
+        .iter()
+        .filter(|n| export_map.get(*n).is_some_and(|(file_path, _)| file_path.is_empty()))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+      let residual_request = format!(
+        "__barrel_optimize__?names={}!=!{}?{}",
+        local_names.join(","),
+        resource_path,
+        BARREL_RESIDUAL_QUERY
+      );
+      // Side effects of the local statements run even if no local export is requested.
+      if side_effects && local_names.is_empty() {
+        output.push_str(&format!("\nimport '{}';", residual_request));
+      }
+      let context = resource_path.parent().map(|p| p.as_std_path()).unwrap_or(Path::new(""));
+      // Map of generated line -> (line, column) of the export in the barrel file.
+      let mut mappings = vec![];
+      let mut targets = vec![];
       names.iter().for_each(|n| {
+        if let Some(location) = locations.get(n) {
+          mappings.push((output.matches('\n').count() as u32 + 1, *location));
+        }
         if export_map.contains_key(n) {
           let (file_path, orig) = export_map.get(n).unwrap();
-          if orig == "*" {
+          if file_path.is_empty() {
+            targets.push(resource_path.to_string());
+          } else {
+            targets.push(resolved_target(&resolutions, context, file_path));
+          }
+          if file_path.is_empty() {
+            output.push_str(&format!("\nexport {{ {} }} from '{}';", n, residual_request));
+          } else if orig == "*" {
             output.push_str(&format!("\nexport * as {} from '{}';", n, file_path));
           } else if orig == "default" {
             output.push_str(&format!(
@@ -165,41 +245,91 @@ impl BarrelLoader {
               orig, n, file_path
             ));
           }
+        } else if n == "default" {
+          // `export *` never re-exports the default export, and the export list of a CommonJS
+          // barrel file has none, so it is served by the barrel file itself.
+          output.push_str(&format!("\nexport {{ default }} from '{}';", resource_path));
+          targets.push(resource_path.to_string());
         } else {
           missed_names.push(n.as_str());
         }
       });
 
+      // Names missing from the export list fan out to every `export *` of the barrel file.
+      let fallback = !missed_names.is_empty() && !wildcard_exports.is_empty();
       if missed_names.len() > 0 {
         wildcard_exports.iter().for_each(|n| {
           let mut missed_str = String::from(&missed_names.join(" ,"));
           missed_str.push_str("&wildcard");
-          let req = n.replace("__PLACEHOLDER__", &missed_str);
-          output.push_str(&format!("\nexport * from '{}';", req));
+          output.push_str(&format!(
+            "\nexport * from '__barrel_optimize__?names={}!=!{}';",
+            missed_str, n
+          ));
+          targets.push(resolved_target(&resolutions, context, n));
         });
       }
-      loader_context.finish_with((output, None));
+      record_barrel_report(
+        loader_context,
+        &BarrelReport {
+          resource: resource_path.to_string(),
+          names: names.clone(),
+          targets,
+          export_count: export_list.len() as u32,
+          wildcard_depth,
+          fallback,
+        },
+      )?;
+      let source_map = get_reexport_source_map(
+        resource_path.as_str(),
+        &source,
+        &mappings,
+        loader_context.context.module_source_map_kind,
+      )?;
+      loader_context.finish_with((output, source_map));
     } else {
-      let reexport_str = format!(
+      let mut reexport_str = format!(
         "export * from '{}';",
         resource_path.to_string()
       );
+      // Default imports are also redirected to the barrel optimizer.
+      if self.loader_options.names.iter().any(|n| n == "default") {
+        reexport_str.push_str(&format!("\nexport {{ default }} from '{}';", resource_path));
+      }
+      record_barrel_report(
+        loader_context,
+        &BarrelReport {
+          resource: resource_path.to_string(),
+          names: self.loader_options.names.clone(),
+          targets: vec![resource_path.to_string()],
+          fallback: true,
+          ..Default::default()
+        },
+      )?;
       loader_context.finish_with((reexport_str, None));
     }
     Ok(())
   }
 }
 
-#[derive(Debug, Clone)]
-struct TransformMapping {
-  pub export_list: Vec<Vec<String>>,
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TransformMapping {
+  pub export_list: Vec<ExportEntry>,
   pub wildcard_exports: Vec<String>,
   pub is_client_entry: bool,
-}
-
-lazy_static! {
-  static ref GLOBAL_TRANSFORM_MAPPING: Arc<Mutex<HashMap<String, TransformMapping>>> =
-    Arc::new(Mutex::new(HashMap::new()));
+  // The barrel file and all files reached through `export *`.
+  pub files: Vec<PathBuf>,
+  // Map of "export name" -> (line, column) of the export in the barrel file.
+  #[serde(default)]
+  pub locations: HashMap<String, (u32, u32)>,
+  // Deepest level of nested `export *` followed to collect the export list.
+  #[serde(default)]
+  pub wildcard_depth: u32,
+  // Whether the residual module has side effects, such as `import './style.css'`.
+  #[serde(default)]
+  pub side_effects: bool,
+  // Re-export requests resolved to collect the export list, including the nested `export *`.
+  #[serde(default)]
+  pub resolutions: Vec<WildcardResolution>,
 }
 
 async fn get_barrel_map(
@@ -222,112 +352,94 @@ async fn get_barrel_map(
   } else {
     source.unwrap()
   };
-  let mut swc_options = Options {
-    ..Default::default()
-  };
-  swc_options.config.jsc.target = Some(EsVersion::EsNext);
-  let file_extension = file.extension().unwrap();
-  let ts_extensions = vec!["tsx", "ts", "mts"];
-  if ts_extensions.iter().any(|ext| ext == &file_extension) {
-    swc_options.config.jsc.syntax = Some(Syntax::Typescript(TsSyntax {
-      tsx: true,
-      decorators: true,
-      ..Default::default()
-    }));
-  }
-  swc_options.config.jsc.experimental.cache_root = cache_dir.clone();
+  let swc_options = get_swc_options(&file, cache_dir.clone())?;
 
-  let code = {
+  let analysis = {
     // Drop the block for SwcCompiler will create Rc.
     let c = SwcCompiler::new(file.clone(), content, swc_options).map_err(AnyhowError::from)?;
     let built = c
-      .parse(None, |_| {
-        optimize_barrel(swc_optimize_barrel::Config {
-          wildcard: is_wildcard,
-        })
-      })
+      .parse(None, |_| noop_pass())
       .map_err(AnyhowError::from)?;
-
-    let input_source_map = c
-      .input_source_map(&built.input_source_map)
-      .map_err(|e| error!(e.to_string()))?;
-    let codegen_options = ast::CodegenOptions {
-      target: Some(built.target),
-      minify: Some(built.minify),
-      input_source_map: input_source_map.as_ref(),
-      ascii_only: built
-        .output
-        .charset
-        .as_ref()
-        .map(|v| matches!(v, OutputCharset::Ascii)),
-      source_map_config: SourceMapConfig {
-        enable: false,
-        inline_sources_content: false,
-        emit_columns: false,
-        names: Default::default(),
-      },
-      inline_script: Some(false),
-      keep_comments: Some(true),
-    };
-    let program = c.transform(built).map_err(AnyhowError::from)?;
-    let ast = c.into_js_ast(program);
-    let TransformOutput { code, map: _ } = ast::stringify(&ast, codegen_options)?;
-    code
+    built
+      .program
+      .as_module()
+      .and_then(|module| {
+        analyze_barrel(
+          module,
+          &swc_optimize_barrel::Config {
+            wildcard: is_wildcard,
+            partial: !is_wildcard,
+          },
+        )
+      })
+      .map(|analysis| {
+        let locations = get_export_locations(c.cm(), &analysis.exports);
+        (analysis, locations)
+      })
   };
-  let regex =
-    Regex::new(r#"^(.|\n)*export (const|var) __next_private_export_map__ = ('[^']+'|\"[^\"]+\")"#)
-      .unwrap();
-
-  if let Some(captures) = regex.captures(&code) {
-    let directive_regex =
-      Regex::new(r#"^(.|\n)*export (const|var) __next_private_directive_list__ = '([^']+)'"#)
-        .unwrap();
-    let directive_list: Vec<String> =
-      if let Some(directive_captures) = directive_regex.captures(&code) {
-        let matched_str = directive_captures.get(3).unwrap().as_str().to_string();
-        serde_json::from_str::<Vec<String>>(&matched_str).unwrap()
-      } else {
-        vec![]
-      };
-    let is_client_entry = directive_list
-      .iter()
-      .any(|directive| directive.contains("use client"));
-    let export_str = captures.get(3).unwrap().as_str().to_string();
-    let slice_str = &export_str[1..export_str.len() - 1];
-    let format_list = serde_json::from_str::<Vec<Vec<String>>>(slice_str).unwrap();
-    let wildcard_regex = Regex::new(r#"export \* from "([^"]+)""#).unwrap();
-    let wildcard_exports = wildcard_regex
-      .captures_iter(&code)
-      .filter_map(|cap| cap.get(1))
-      .map(|m| m.as_str().to_owned())
-      .collect::<Vec<String>>();
-
-    let mut export_list = format_list
-      .iter()
-      .map(|list| {
+
+  if let Some((analysis, locations)) = analysis {
+    let is_client_entry = analysis.is_client_entry();
+    let BarrelAnalysis {
+      exports,
+      wildcard_exports,
+      directives: _,
+      side_effects,
+    } = analysis;
+
+    let mut export_list = exports
+      .into_iter()
+      .map(|entry| {
         if is_wildcard {
-          vec![
-            list[0].clone(),
-            file.clone().to_string_lossy().to_string(),
-            list[0].clone(),
-          ]
+          ExportEntry {
+            src: file.to_string_lossy().to_string(),
+            orig: entry.name.clone(),
+            ..entry
+          }
         } else {
-          list.clone()
+          entry
         }
       })
-      .collect::<Vec<Vec<String>>>();
+      .collect::<Vec<ExportEntry>>();
 
+    let context = file
+      .parent()
+      .ok_or_else(|| error!("Failed to resolve the re-exports of {}", file.display()))?;
+    let mut resolutions = vec![];
+    if !is_wildcard {
+      // Sources of named re-exports are resolved once here for the barrel report,
+      // requests failing to resolve are reported as is.
+      let requests = export_list
+        .iter()
+        .map(|entry| entry.src.as_str())
+        .filter(|src| !src.is_empty())
+        .collect::<BTreeSet<_>>();
+      for request in requests {
+        resolutions.push(match resolver.resolve(context, request) {
+          Ok(result) => WildcardResolution::new(context, request, &result),
+          Err(_) => WildcardResolution {
+            context: context.to_path_buf(),
+            request: request.to_string(),
+            resolved: None,
+          },
+        });
+      }
+    }
+
+    let mut files = vec![file.clone()];
+    let mut wildcard_depth = 0;
     if wildcard_exports.len() > 0 {
       for req in &wildcard_exports {
-        let real_req = req.replace("__barrel_optimize__?names=__PLACEHOLDER__!=!", "");
         let wildcard_resolve = resolver
-          .resolve(&file.parent().unwrap().to_path_buf(), &real_req)
+          .resolve(context, req)
           .map_err(|err| error!("Failed to resolve {err:?}"))?;
+        resolutions.push(WildcardResolution::new(context, req, &wildcard_resolve));
         if let ResolveResult::Resource(resource) = wildcard_resolve {
+          let resource_path = resource.path.as_std_path().to_path_buf();
           let res = get_barrel_map_boxed(
             visited.clone(),
             resolver.clone(),
-            resource.path.as_std_path().to_path_buf(),
+            resource_path.clone(),
             cache_dir.clone(),
             true,
             None,
@@ -337,9 +449,21 @@ async fn get_barrel_map(
             export_list: sub_export_list,
             wildcard_exports: _,
             is_client_entry: _,
+            files: sub_files,
+            locations: _,
+            wildcard_depth: sub_wildcard_depth,
+            side_effects: _,
+            resolutions: sub_resolutions,
           }) = res
           {
+            wildcard_depth = wildcard_depth.max(sub_wildcard_depth + 1);
             export_list.extend(sub_export_list);
+            files.extend(sub_files);
+            resolutions.extend(sub_resolutions);
+          } else {
+            wildcard_depth = wildcard_depth.max(1);
+            // Keep track of the file even if it is not a barrel file for now.
+            files.push(resource_path);
           }
         }
       }
@@ -348,12 +472,141 @@ async fn get_barrel_map(
       export_list,
       wildcard_exports,
       is_client_entry,
+      files,
+      locations,
+      wildcard_depth,
+      // Files reached through `export *` are imported as a whole.
+      side_effects: side_effects && !is_wildcard,
+      resolutions,
     };
     return Ok(Some(ret));
   }
   return Ok(None);
 }
 
+/// The module a re-export request of the barrel file is redirected to, from the resolutions
+/// of its mapping. Requests which failed to resolve are reported as is, as well as the files
+/// reached through `export *` which are already absolute paths.
+pub fn resolved_target(
+  resolutions: &[WildcardResolution],
+  context: &Path,
+  request: &str,
+) -> String {
+  resolutions
+    .iter()
+    .find(|resolution| resolution.context == context && resolution.request == request)
+    .and_then(|resolution| resolution.resolved.as_ref())
+    .map(|resolved| resolved.to_string_lossy().to_string())
+    .unwrap_or_else(|| request.to_string())
+}
+
+/// Map of "export name" -> zero based (line, column) of the export in the barrel file.
+pub fn get_export_locations(
+  cm: &swc_core::common::SourceMap,
+  exports: &[ExportEntry],
+) -> HashMap<String, (u32, u32)> {
+  exports
+    .iter()
+    .filter(|entry| !entry.span.is_dummy())
+    .map(|entry| {
+      let loc = cm.lookup_char_pos(entry.span.lo);
+      (entry.name.clone(), (loc.line as u32 - 1, loc.col.0 as u32))
+    })
+    .collect()
+}
+
+/// Source map of the re-export module, `mappings` are pairs of generated line and the
+/// location of the export in the barrel file.
+pub fn get_reexport_source_map(
+  file: &str,
+  source: &str,
+  mappings: &[(u32, (u32, u32))],
+  source_map_kind: SourceMapKind,
+) -> Result<Option<SourceMap>> {
+  if !source_map_kind.source_map() || mappings.is_empty() {
+    return Ok(None);
+  }
+  let mut builder = SourceMapBuilder::new(None);
+  let src_id = builder.add_source(file);
+  builder.set_source_contents(src_id, Some(source));
+  for (dst_line, (src_line, src_col)) in mappings {
+    let src_col = if source_map_kind.cheap() { 0 } else { *src_col };
+    builder.add_raw(*dst_line, 0, *src_line, src_col, Some(src_id), None, false);
+  }
+  let mut map = vec![];
+  builder
+    .into_sourcemap()
+    .to_writer(&mut map)
+    .map_err(|e| error!(e.to_string()))?;
+  let map = SourceMap::from_slice(&map).map_err(|e| error!(e.to_string()))?;
+  Ok(Some(map))
+}
+
+fn get_swc_options(file: &Path, cache_dir: Option<String>) -> Result<Options> {
+  let mut swc_options = Options {
+    ..Default::default()
+  };
+  swc_options.config.jsc.target = Some(EsVersion::EsNext);
+  let file_extension = file
+    .extension()
+    .ok_or_else(|| error!("Failed to get the extension of {}", file.display()))?;
+  let ts_extensions = vec!["tsx", "ts", "mts"];
+  if ts_extensions.iter().any(|ext| ext == &file_extension) {
+    swc_options.config.jsc.syntax = Some(Syntax::Typescript(TsSyntax {
+      tsx: true,
+      decorators: true,
+      ..Default::default()
+    }));
+  }
+  swc_options.config.jsc.experimental.cache_root = cache_dir;
+  Ok(swc_options)
+}
+
+// Keep the requested local exports of a partial barrel file and their dependencies,
+// re-exports are dropped as they are redirected to the source modules.
+fn get_residual_module(
+  file: &Path,
+  source: String,
+  names: &[String],
+  cache_dir: Option<String>,
+  source_map_kind: SourceMapKind,
+) -> Result<TransformOutput> {
+  let swc_options = get_swc_options(file, cache_dir)?;
+  let c = SwcCompiler::new(file.to_path_buf(), source, swc_options).map_err(AnyhowError::from)?;
+  let built = c
+    .parse(None, |_| {
+      // Requested names are exact export names rather than patterns.
+      let names = names.iter().map(|name| ExportPattern::from(name.as_str()));
+      keep_residual_exports(names.collect())
+    })
+    .map_err(AnyhowError::from)?;
+
+  let input_source_map = c
+    .input_source_map(&built.input_source_map)
+    .map_err(|e| error!(e.to_string()))?;
+  let codegen_options = ast::CodegenOptions {
+    target: Some(built.target),
+    minify: Some(built.minify),
+    input_source_map: input_source_map.as_ref(),
+    ascii_only: built
+      .output
+      .charset
+      .as_ref()
+      .map(|v| matches!(v, OutputCharset::Ascii)),
+    source_map_config: SourceMapConfig {
+      enable: source_map_kind.source_map(),
+      inline_sources_content: source_map_kind.source_map(),
+      emit_columns: !source_map_kind.cheap(),
+      names: Default::default(),
+    },
+    inline_script: Some(false),
+    keep_comments: Some(true),
+  };
+  let program = c.transform(built).map_err(AnyhowError::from)?;
+  let ast = c.into_js_ast(program);
+  ast::stringify(&ast, codegen_options)
+}
+
 // A boxed function that can be sent across threads
 fn get_barrel_map_boxed(
   visited: HashSet<PathBuf>,
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use rspack_core::{
+  ApplyContext, BoxLoader, Compilation, CompilationParams, CompilerCompilation, CompilerFinishMake,
+  CompilerOptions, Context, ModuleRuleUseLoader, NormalModuleFactoryResolveLoader, Plugin,
+  PluginContext, Resolver,
+};
+use rspack_error::{error, Result};
+use rspack_fs::IntermediateFileSystem;
+use rspack_hook::{plugin, plugin_hook};
+use tokio::sync::RwLock;
+
+use crate::{cache::BarrelMappingCaches, BarrelLoader, BARREL_LOADER_IDENTIFIER};
+
+/// Owns the barrel mapping caches of a compiler, and creates `builtin:barrel-loader` with them.
+///
+/// The plugin must be applied before `JsLoaderRspackPlugin`, which resolves builtin loaders
+/// without any compiler state.
+#[plugin]
+#[derive(Debug)]
+pub struct BarrelLoaderPlugin {
+  caches: Arc<BarrelMappingCaches>,
+  // Loaders by request and options, so that the loader is not created for every module.
+  loaders: RwLock<HashMap<(String, String), Arc<BarrelLoader>>>,
+}
+
+impl BarrelLoaderPlugin {
+  /// Mappings are only persisted with the intermediate filesystem of the compiler.
+  pub fn new(
+    compiler_options: &CompilerOptions,
+    intermediate_fs: Option<Arc<dyn IntermediateFileSystem>>,
+  ) -> Self {
+    Self::new_inner(
+      Arc::new(BarrelMappingCaches::new(compiler_options, intermediate_fs)),
+      Default::default(),
+    )
+  }
+}
+
+#[plugin_hook(CompilerCompilation for BarrelLoaderPlugin)]
+async fn compilation(
+  &self,
+  compilation: &mut Compilation,
+  _params: &mut CompilationParams,
+) -> Result<()> {
+  // The content hash of a barrel file does not cover the files reached through `export *`.
+  let changed_files = compilation
+    .modified_files
+    .iter()
+    .chain(compilation.removed_files.iter())
+    .map(|file| -> &Path { file.as_ref() });
+  self.caches.memory.invalidate(changed_files);
+  Ok(())
+}
+
+#[plugin_hook(CompilerFinishMake for BarrelLoaderPlugin)]
+async fn finish_make(&self, _compilation: &mut Compilation) -> Result<()> {
+  // Every barrel file of the compilation is transformed once make is finished.
+  self.caches.save().await;
+  Ok(())
+}
+
+#[plugin_hook(NormalModuleFactoryResolveLoader for BarrelLoaderPlugin)]
+async fn resolve_loader(
+  &self,
+  _context: &Context,
+  _resolver: &Resolver,
+  l: &ModuleRuleUseLoader,
+) -> Result<Option<BoxLoader>> {
+  if !l.loader.starts_with(BARREL_LOADER_IDENTIFIER) {
+    return Ok(None);
+  }
+  let key = (
+    l.loader.clone(),
+    l.options.clone().unwrap_or_else(|| "{}".to_string()),
+  );
+  if let Some(loader) = self.loaders.read().await.get(&key) {
+    return Ok(Some(loader.clone()));
+  }
+  let loader = Arc::new(
+    BarrelLoader::new(&key.1)
+      .map_err(|e| error!("failed to parse builtin:barrel-loader options: {e}"))?
+      .with_identifier(key.0.as_str().into())
+      .with_caches(self.caches.clone()),
+  );
+  self.loaders.write().await.insert(key, loader.clone());
+  Ok(Some(loader))
+}
+
+impl Plugin for BarrelLoaderPlugin {
+  fn name(&self) -> &'static str {
+    "BarrelLoaderPlugin"
+  }
+
+  fn apply(&self, ctx: PluginContext<&mut ApplyContext>, _options: &CompilerOptions) -> Result<()> {
+    ctx
+      .context
+      .compiler_hooks
+      .compilation
+      .tap(compilation::new(self));
+    ctx
+      .context
+      .compiler_hooks
+      .finish_make
+      .tap(finish_make::new(self));
+    ctx
+      .context
+      .normal_module_factory_hooks
+      .resolve_loader
+      .tap(resolve_loader::new(self));
+    Ok(())
+  }
+}
@@ -0,0 +1,68 @@
+use rspack_core::RunnerContext;
+use rspack_error::{error, Result};
+use rspack_loader_runner::LoaderContext;
+use serde::{Deserialize, Serialize};
+
+/// Key of the barrel report in `module.buildInfo.parseMeta`.
+pub const BARREL_REPORT_META_KEY: &str = "barrelLoader.report";
+
+/// How the barrel loader handled a barrel file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BarrelReport {
+  /// Path of the barrel file.
+  pub resource: String,
+  /// Export names requested from the barrel file.
+  pub names: Vec<String>,
+  /// Resolved paths of the modules the requested names are redirected to.
+  pub targets: Vec<String>,
+  /// Number of exports found in the barrel file, including the ones reached through `export *`.
+  pub export_count: u32,
+  /// Deepest level of nested `export *` followed to collect the exports.
+  pub wildcard_depth: u32,
+  /// Whether the barrel file could not be optimized and was re-exported as a whole.
+  pub fallback: bool,
+}
+
+impl BarrelReport {
+  fn merge(&mut self, other: BarrelReport) {
+    self.names.extend(other.names);
+    self.names.sort();
+    self.names.dedup();
+    self.targets.extend(other.targets);
+    self.targets.sort();
+    self.targets.dedup();
+    self.export_count = self.export_count.max(other.export_count);
+    self.wildcard_depth = self.wildcard_depth.max(other.wildcard_depth);
+    self.fallback |= other.fallback;
+  }
+}
+
+// Stored in the parse meta of the module, so it is cached along with the module.
+pub(crate) fn record_barrel_report(
+  loader_context: &mut LoaderContext<RunnerContext>,
+  report: &BarrelReport,
+) -> Result<()> {
+  let report = serde_json::to_string(report).map_err(|e| error!(e.to_string()))?;
+  loader_context
+    .parse_meta
+    .insert(BARREL_REPORT_META_KEY.to_string(), report);
+  Ok(())
+}
+
+/// Collect the reports from the parse meta of the built modules. A barrel file is requested
+/// with different names by each importer, their reports are merged by the barrel file.
+pub fn collect_barrel_reports<'a>(reports: impl IntoIterator<Item = &'a str>) -> Vec<BarrelReport> {
+  let mut collected: Vec<BarrelReport> = vec![];
+  for report in reports {
+    let Ok(report) = serde_json::from_str::<BarrelReport>(report) else {
+      continue;
+    };
+    match collected.iter_mut().find(|r| r.resource == report.resource) {
+      Some(existing) => existing.merge(report),
+      None => collected.push(report),
+    }
+  }
+  collected.sort_by(|a, b| a.resource.cmp(&b.resource));
+  collected
+}
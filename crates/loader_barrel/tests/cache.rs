@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use loader_barrel::{content_hash, MemoryCache};
+
+fn path(name: &str) -> PathBuf {
+  Path::new("/project/src").join(name)
+}
+
+#[test]
+fn test_hit_when_content_unchanged() {
+  let cache = MemoryCache::new(8);
+  let barrel = path("index.js");
+  let hash = content_hash(b"export * from './a';");
+  cache.insert(barrel.clone(), hash, "mapping", vec![barrel.clone()]);
+  assert_eq!(cache.get(&barrel, hash), Some("mapping"));
+  assert_eq!(cache.get(&barrel, hash), Some("mapping"));
+}
+
+#[test]
+fn test_miss_when_content_changes() {
+  let cache = MemoryCache::new(8);
+  let barrel = path("index.js");
+  cache.insert(
+    barrel.clone(),
+    content_hash(b"export * from './a';"),
+    "mapping",
+    vec![barrel.clone()],
+  );
+  assert_eq!(
+    cache.get(&barrel, content_hash(b"export * from './b';")),
+    None
+  );
+}
+
+#[test]
+fn test_evict_when_wildcard_target_is_modified() {
+  let cache = MemoryCache::new(8);
+  let barrel = path("index.js");
+  let target = path("a.js");
+  let hash = content_hash(b"export * from './a';");
+  cache.insert(
+    barrel.clone(),
+    hash,
+    "mapping",
+    vec![barrel.clone(), target.clone()],
+  );
+  cache.invalidate([path("unrelated.js").as_path()]);
+  assert_eq!(cache.get(&barrel, hash), Some("mapping"));
+  cache.invalidate([target.as_path()]);
+  assert_eq!(cache.get(&barrel, hash), None);
+  assert!(cache.is_empty());
+}
+
+#[test]
+fn test_evict_oldest_when_full() {
+  let cache = MemoryCache::new(2);
+  let hash = content_hash(b"");
+  for name in ["a.js", "b.js", "c.js"] {
+    cache.insert(path(name), hash, name, vec![path(name)]);
+  }
+  assert_eq!(cache.len(), 2);
+  assert_eq!(cache.get(&path("a.js"), hash), None);
+  assert_eq!(cache.get(&path("b.js"), hash), Some("b.js"));
+  assert_eq!(cache.get(&path("c.js"), hash), Some("c.js"));
+}
+
+#[test]
+fn test_reinsert_does_not_duplicate_order() {
+  let cache = MemoryCache::new(2);
+  let hash = content_hash(b"");
+  cache.insert(path("a.js"), hash, "a1", vec![]);
+  cache.insert(path("a.js"), hash, "a2", vec![]);
+  cache.insert(path("b.js"), hash, "b", vec![]);
+  assert_eq!(cache.len(), 2);
+  assert_eq!(cache.get(&path("a.js"), hash), Some("a2"));
+}
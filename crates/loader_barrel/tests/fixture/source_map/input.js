@@ -0,0 +1,10 @@
+import './style.css';
+
+export { Button } from './button';
+export {
+  Input,
+  Select as Picker,
+} from './form';
+export * as icons from './icons';
+
+export { default as Table } from './table';
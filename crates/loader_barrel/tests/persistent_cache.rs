@@ -0,0 +1,133 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use loader_barrel::{PersistentMappingCache, TransformMapping, WildcardResolution};
+use rspack_core::cache::persistent::storage::{create_storage, StorageOptions};
+use rspack_fs::NativeFileSystem;
+
+const BARREL: &str = "export * from './a';";
+
+// Every test works in its own directory so that tests can run in parallel.
+fn temp_dir(name: &str) -> PathBuf {
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_nanos();
+  let dir = std::env::temp_dir().join(format!(
+    "loader_barrel_persistent_cache_{name}_{}_{nanos}",
+    std::process::id()
+  ));
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+fn create_cache(dir: &Path, resolve_options_hash: u64) -> PersistentMappingCache {
+  let storage = create_storage(
+    StorageOptions::FileSystem {
+      directory: dir.join("cache").to_string_lossy().as_ref().into(),
+    },
+    "test".to_string(),
+    Arc::new(NativeFileSystem::new(false)),
+  );
+  PersistentMappingCache::new(storage, resolve_options_hash)
+}
+
+// A barrel file `index.js` re-exporting everything from `a.js`.
+fn create_barrel(dir: &Path) -> (PathBuf, TransformMapping) {
+  let barrel = dir.join("index.js");
+  let target = dir.join("a.js");
+  fs::write(&barrel, BARREL).unwrap();
+  fs::write(&target, "export const a = 1;").unwrap();
+  let mapping = TransformMapping {
+    wildcard_exports: vec!["./a".to_string()],
+    files: vec![barrel.clone(), target.clone()],
+    wildcard_depth: 1,
+    resolutions: vec![WildcardResolution {
+      context: dir.to_path_buf(),
+      request: "./a".to_string(),
+      resolved: Some(target),
+    }],
+    ..Default::default()
+  };
+  (barrel, mapping)
+}
+
+fn resolve(context: &Path, request: &str) -> Option<PathBuf> {
+  Some(
+    context
+      .join(request.trim_start_matches("./"))
+      .with_extension("js"),
+  )
+}
+
+async fn warm_cache(dir: &Path, barrel: &Path, mapping: &TransformMapping) {
+  let cache = create_cache(dir, 0);
+  cache.set(barrel, BARREL, mapping).await;
+  cache.save().await;
+}
+
+#[tokio::test]
+async fn test_hit_across_builds() {
+  let dir = temp_dir("hit");
+  let (barrel, mapping) = create_barrel(&dir);
+  warm_cache(&dir, &barrel, &mapping).await;
+
+  let cached = create_cache(&dir, 0).get(&barrel, BARREL, resolve).await;
+  assert_eq!(cached.map(|mapping| mapping.files), Some(mapping.files));
+}
+
+#[tokio::test]
+async fn test_miss_when_barrel_changes() {
+  let dir = temp_dir("barrel_changes");
+  let (barrel, mapping) = create_barrel(&dir);
+  warm_cache(&dir, &barrel, &mapping).await;
+
+  let cache = create_cache(&dir, 0);
+  assert!(cache
+    .get(&barrel, "export * from './b';", resolve)
+    .await
+    .is_none());
+}
+
+#[tokio::test]
+async fn test_miss_when_resolve_options_change() {
+  let dir = temp_dir("resolve_options");
+  let (barrel, mapping) = create_barrel(&dir);
+  warm_cache(&dir, &barrel, &mapping).await;
+
+  assert!(create_cache(&dir, 1)
+    .get(&barrel, BARREL, resolve)
+    .await
+    .is_none());
+}
+
+#[tokio::test]
+async fn test_miss_when_wildcard_target_changes() {
+  let dir = temp_dir("target_changes");
+  let (barrel, mapping) = create_barrel(&dir);
+  warm_cache(&dir, &barrel, &mapping).await;
+  fs::write(dir.join("a.js"), "export const b = 1;").unwrap();
+
+  assert!(create_cache(&dir, 0)
+    .get(&barrel, BARREL, resolve)
+    .await
+    .is_none());
+}
+
+#[tokio::test]
+async fn test_miss_when_wildcard_resolves_elsewhere() {
+  let dir = temp_dir("resolves_elsewhere");
+  let (barrel, mapping) = create_barrel(&dir);
+  warm_cache(&dir, &barrel, &mapping).await;
+
+  let resolve_to_index =
+    |context: &Path, request: &str| Some(context.join(request).join("index.js"));
+  assert!(create_cache(&dir, 0)
+    .get(&barrel, BARREL, resolve_to_index)
+    .await
+    .is_none());
+}
@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+
+use loader_barrel::{collect_barrel_reports, resolved_target, BarrelReport, WildcardResolution};
+
+fn report(resource: &str, names: &[&str], targets: &[&str], fallback: bool) -> String {
+  serde_json::to_string(&BarrelReport {
+    resource: resource.to_string(),
+    names: names.iter().map(|n| n.to_string()).collect(),
+    targets: targets.iter().map(|t| t.to_string()).collect(),
+    export_count: 3,
+    wildcard_depth: 1,
+    fallback,
+  })
+  .unwrap()
+}
+
+#[test]
+fn test_collect_barrel_reports() {
+  let reports = [
+    report(
+      "/app/ui/index.js",
+      &["Button"],
+      &["/app/ui/button.js"],
+      false,
+    ),
+    report(
+      "/app/icons/index.js",
+      &["Add"],
+      &["/app/icons/add.js"],
+      false,
+    ),
+    // Names missing from the export list fan out to `export *`.
+    report(
+      "/app/ui/index.js",
+      &["Button", "Missing"],
+      &["/app/ui/button.js", "/app/ui/form.js"],
+      true,
+    ),
+    "invalid report".to_string(),
+  ];
+  let collected = collect_barrel_reports(reports.iter().map(String::as_str));
+  assert_eq!(collected.len(), 2);
+  assert_eq!(collected[0].resource, "/app/icons/index.js");
+  assert!(!collected[0].fallback);
+
+  let ui = &collected[1];
+  assert_eq!(ui.names, vec!["Button", "Missing"]);
+  assert_eq!(ui.targets, vec!["/app/ui/button.js", "/app/ui/form.js"]);
+  // A barrel file is reported as a fallback if any importer fell back.
+  assert!(ui.fallback);
+}
+
+#[test]
+fn test_resolved_target() {
+  let context = Path::new("/app/ui");
+  let resolutions = [
+    WildcardResolution {
+      context: context.to_path_buf(),
+      request: "./button".to_string(),
+      resolved: Some(PathBuf::from("/app/ui/button/index.js")),
+    },
+    WildcardResolution {
+      context: context.to_path_buf(),
+      request: "./missing".to_string(),
+      resolved: None,
+    },
+    WildcardResolution {
+      context: PathBuf::from("/app/ui/form"),
+      request: "./input".to_string(),
+      resolved: Some(PathBuf::from("/app/ui/form/input.js")),
+    },
+  ];
+  assert_eq!(
+    resolved_target(&resolutions, context, "./button"),
+    "/app/ui/button/index.js"
+  );
+  assert_eq!(
+    resolved_target(&resolutions, context, "./missing"),
+    "./missing"
+  );
+  // Requests are resolved from the directory of the barrel file they come from.
+  assert_eq!(resolved_target(&resolutions, context, "./input"), "./input");
+  // Files reached through `export *` are already resolved.
+  assert_eq!(
+    resolved_target(&resolutions, context, "/app/ui/form/index.js"),
+    "/app/ui/form/index.js"
+  );
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use loader_barrel::{get_export_locations, get_reexport_source_map};
+use rspack_util::source_map::SourceMapKind;
+use swc_core::base::sourcemap;
+use swc_core::common::{sync::Lrc, FileName, SourceMap};
+use swc_core::ecma::ast::EsVersion;
+use swc_core::ecma::parser::{parse_file_as_module, Syntax};
+use swc_optimize_barrel::{analyze_barrel, Config};
+
+// Requested names and the start of their export in the barrel file.
+const EXPORTS: [(&str, &str); 4] = [
+  ("Picker", "Select as Picker,"),
+  ("Button", "Button }"),
+  ("icons", "* as icons"),
+  ("Table", "default as Table"),
+];
+
+fn reexport_source_map(source_map_kind: SourceMapKind) -> (String, sourcemap::SourceMap) {
+  let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixture/source_map/input.js");
+  let source = std::fs::read_to_string(&input).unwrap();
+  let cm: Lrc<SourceMap> = Default::default();
+  let fm = cm.new_source_file(Lrc::new(FileName::Real(input.clone())), source.clone());
+  let module = parse_file_as_module(
+    &fm,
+    Syntax::Es(Default::default()),
+    EsVersion::latest(),
+    None,
+    &mut vec![],
+  )
+  .expect("failed to parse fixture");
+  let analysis = analyze_barrel(
+    &module,
+    &Config {
+      wildcard: false,
+      partial: false,
+    },
+  )
+  .expect("fixture should be a barrel file");
+  let locations = get_export_locations(&cm, &analysis.exports);
+  // The re-export module has a line for each requested name after the first line.
+  let mappings = EXPORTS
+    .iter()
+    .enumerate()
+    .map(|(index, (name, _))| (index as u32 + 1, locations[*name]))
+    .collect::<Vec<_>>();
+  let map = get_reexport_source_map(input.to_str().unwrap(), &source, &mappings, source_map_kind)
+    .unwrap()
+    .expect("source map should be generated");
+  let map = sourcemap::SourceMap::from_slice(map.to_json().unwrap().as_bytes()).unwrap();
+  (source, map)
+}
+
+#[test]
+fn test_reexport_source_map() {
+  let (source, map) = reexport_source_map(SourceMapKind::SourceMap);
+  let lines = source.lines().collect::<Vec<_>>();
+  for (index, (name, export)) in EXPORTS.iter().enumerate() {
+    let token = map
+      .lookup_token(index as u32 + 1, 0)
+      .unwrap_or_else(|| panic!("`{name}` is not mapped"));
+    let line = lines[token.get_src_line() as usize];
+    assert!(
+      line[token.get_src_col() as usize..].starts_with(export),
+      "`{name}` is mapped to `{line}`"
+    );
+  }
+  assert_eq!(map.get_source_contents(0), Some(source.as_str()));
+}
+
+#[test]
+fn test_cheap_reexport_source_map() {
+  let (source, map) = reexport_source_map(SourceMapKind::SourceMap | SourceMapKind::Cheap);
+  let lines = source.lines().collect::<Vec<_>>();
+  for (index, (name, export)) in EXPORTS.iter().enumerate() {
+    let token = map.lookup_token(index as u32 + 1, 0).unwrap();
+    // Cheap source maps only point to the line of the export.
+    assert_eq!(token.get_src_col(), 0);
+    assert!(
+      lines[token.get_src_line() as usize].contains(export),
+      "`{name}` is mapped to the wrong line"
+    );
+  }
+}
+
+#[test]
+fn test_no_source_map() {
+  assert!(
+    get_reexport_source_map("input.js", "", &[(1, (0, 0))], SourceMapKind::empty())
+      .unwrap()
+      .is_none()
+  );
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rspack_core::rspack_sources::SourceMap;
+use rspack_fs::IntermediateFileSystem;
+use rspack_paths::{Utf8Path, Utf8PathBuf};
+use rspack_plugin_javascript::TransformOutput;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::Xxh3;
+
+const DEFAULT_CACHE_DIRECTORY: &str = "node_modules/.cache/compilation-loader";
+
+// Bump when the transforms change their output for the same input, so stale entries are missed.
+const TRANSFORM_CACHE_VERSION: u32 = 1;
+
+// Same as the default `cache.maxAge` of webpack, 60 days.
+const DEFAULT_MAX_AGE: u64 = 1000 * 60 * 60 * 24 * 60;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TransformCacheOptions {
+  // Directory of the cached transform outputs, relative to the compiler context.
+  pub directory: Option<String>,
+  // Entries not written for longer than this, in milliseconds, are removed.
+  pub max_age: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedOutput {
+  code: String,
+  map: Option<String>,
+  #[serde(default)]
+  pruned_exports: Option<String>,
+  #[serde(default)]
+  side_effects: BTreeMap<String, bool>,
+}
+
+/// A cached transform output.
+pub struct CachedTransform {
+  pub output: TransformOutput,
+  /// Pruned exports summary, see `export_summary_to_json`.
+  pub pruned_exports: Option<String>,
+  /// Whether each import source had side effects when the output was cached. These depend on
+  /// files outside the module such as package.json, so they are checked again on a hit.
+  pub side_effects: BTreeMap<String, bool>,
+}
+
+impl CachedTransform {
+  /// Whether every import source still has the side effects it had when the output was cached.
+  pub fn side_effects_unchanged(&self, has_side_effects: impl Fn(&str) -> bool) -> bool {
+    self
+      .side_effects
+      .iter()
+      .all(|(src, cached)| has_side_effects(src) == *cached)
+  }
+}
+
+/// Transform outputs of the compilation loader stored on disk, keyed by everything
+/// the output depends on, see `TransformCache::key`.
+#[derive(Debug)]
+pub struct TransformCache {
+  fs: Arc<dyn IntermediateFileSystem>,
+  directory: Utf8PathBuf,
+  max_age: u64,
+}
+
+impl TransformCache {
+  /// `fs` is the intermediate filesystem of the compiler, and `context` its context.
+  pub fn new(
+    fs: Arc<dyn IntermediateFileSystem>,
+    context: &Utf8Path,
+    options: &TransformCacheOptions,
+  ) -> Self {
+    Self {
+      fs,
+      directory: context.join(
+        options
+          .directory
+          .as_deref()
+          .unwrap_or(DEFAULT_CACHE_DIRECTORY),
+      ),
+      max_age: options.max_age.unwrap_or(DEFAULT_MAX_AGE),
+    }
+  }
+
+  pub fn directory(&self) -> &Utf8Path {
+    &self.directory
+  }
+
+  pub fn key(parts: &[&[u8]]) -> u64 {
+    let mut hasher = Xxh3::new();
+    hasher.update(&TRANSFORM_CACHE_VERSION.to_le_bytes());
+    for part in parts {
+      // Prefix the length so that different splits of the same bytes never collide.
+      hasher.update(&(part.len() as u64).to_le_bytes());
+      hasher.update(part);
+    }
+    hasher.digest()
+  }
+
+  fn entry_path(&self, key: u64) -> Utf8PathBuf {
+    self.directory.join(format!("{key:016x}.json"))
+  }
+
+  pub async fn get(&self, key: u64) -> Option<CachedTransform> {
+    let content = self.fs.read_file(&self.entry_path(key)).await.ok()?;
+    let cached: CachedOutput = serde_json::from_slice(&content).ok()?;
+    let map = match cached.map {
+      Some(map) => Some(SourceMap::from_json(&map).ok()?),
+      None => None,
+    };
+    Some(CachedTransform {
+      output: TransformOutput {
+        code: cached.code,
+        map,
+      },
+      pruned_exports: cached.pruned_exports,
+      side_effects: cached.side_effects,
+    })
+  }
+
+  pub async fn set(
+    &self,
+    key: u64,
+    output: &TransformOutput,
+    pruned_exports: Option<&String>,
+    side_effects: BTreeMap<String, bool>,
+  ) {
+    let map = match &output.map {
+      Some(map) => match map.to_json() {
+        Ok(map) => Some(map),
+        Err(_) => return,
+      },
+      None => None,
+    };
+    let cached = CachedOutput {
+      code: output.code.clone(),
+      map,
+      pruned_exports: pruned_exports.cloned(),
+      side_effects,
+    };
+    let Ok(content) = serde_json::to_vec(&cached) else {
+      return;
+    };
+    // Failing to write the cache should never fail the build.
+    if self.fs.create_dir_all(&self.directory).await.is_ok() {
+      let _ = self.fs.write(&self.entry_path(key), &content).await;
+    }
+  }
+
+  /// Remove the entries older than `maxAge`.
+  pub async fn evict_stale_entries(&self) {
+    let Ok(files) = self.fs.read_dir(&self.directory).await else {
+      return;
+    };
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_millis() as u64)
+      .unwrap_or_default();
+    for file in files.iter().filter(|file| file.ends_with(".json")) {
+      let path = self.directory.join(file);
+      if let Ok(metadata) = self.fs.stat(&path).await
+        && now.saturating_sub(metadata.mtime_ms) > self.max_age
+      {
+        let _ = self.fs.remove_file(&path).await;
+      }
+    }
+  }
+}
@@ -0,0 +1,128 @@
+use serde::Deserialize;
+use rspack_regex::RspackRegex;
+use swc_config::merge::Merge;
+use swc_core::base::config::Config;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RawPattern {
+  // Plain strings are regular expressions.
+  Regex(String),
+  Glob { glob: String },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawCompileRule {
+  test: Vec<RawPattern>,
+  swc_options: Config,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RawCompileRules {
+  // Only compile the matched files, all files are compiled if not specified.
+  include: Option<Vec<RawPattern>>,
+  // Built-in rules to exclude files from compilation, such as react, react-dom, etc.
+  exclude: Option<Vec<RawPattern>>,
+  // Override `swcOptions` for the matched files.
+  rules: Option<Vec<RawCompileRule>>,
+}
+
+#[derive(Debug)]
+enum Pattern {
+  Regex(RspackRegex),
+  Glob(glob::Pattern),
+}
+
+impl Pattern {
+  fn test(&self, resource_path: &str) -> bool {
+    match self {
+      Pattern::Regex(regex) => regex.test(resource_path),
+      Pattern::Glob(glob) => glob.matches(resource_path),
+    }
+  }
+}
+
+#[derive(Debug)]
+struct CompileRule {
+  test: Vec<Pattern>,
+  swc_options: Config,
+}
+
+/// Compile rules with patterns compiled once when the loader is created.
+#[derive(Debug, Default)]
+pub struct CompileRules {
+  include: Option<Vec<Pattern>>,
+  exclude: Vec<Pattern>,
+  rules: Vec<CompileRule>,
+}
+
+// `path` locates the patterns in the loader options, such as `compileRules.rules[1].test`.
+fn compile_patterns(patterns: Vec<RawPattern>, path: &str) -> Result<Vec<Pattern>, String> {
+  patterns
+    .into_iter()
+    .enumerate()
+    .map(|(index, pattern)| match pattern {
+      RawPattern::Regex(source) => RspackRegex::new(&source)
+        .map(Pattern::Regex)
+        .map_err(|e| format!("invalid regex `{source}` at {path}[{index}]: {e}")),
+      RawPattern::Glob { glob } => glob::Pattern::new(&glob)
+        .map(Pattern::Glob)
+        .map_err(|e| format!("invalid glob `{glob}` at {path}[{index}]: {e}")),
+    })
+    .collect()
+}
+
+impl CompileRules {
+  pub fn new(raw: RawCompileRules) -> Result<Self, String> {
+    let include = raw
+      .include
+      .map(|patterns| compile_patterns(patterns, "compileRules.include"))
+      .transpose()?;
+    let exclude = raw
+      .exclude
+      .map(|patterns| compile_patterns(patterns, "compileRules.exclude"))
+      .transpose()?
+      .unwrap_or_default();
+    let rules = raw
+      .rules
+      .unwrap_or_default()
+      .into_iter()
+      .enumerate()
+      .map(|(index, rule)| {
+        Ok(CompileRule {
+          test: compile_patterns(rule.test, &format!("compileRules.rules[{index}].test"))?,
+          swc_options: rule.swc_options,
+        })
+      })
+      .collect::<Result<_, String>>()?;
+    Ok(Self {
+      include,
+      exclude,
+      rules,
+    })
+  }
+
+  /// Whether the file should be compiled by the loader.
+  pub fn should_compile(&self, resource_path: &str) -> bool {
+    if self.exclude.iter().any(|pattern| pattern.test(resource_path)) {
+      return false;
+    }
+    match &self.include {
+      Some(include) => include.iter().any(|pattern| pattern.test(resource_path)),
+      None => true,
+    }
+  }
+
+  /// Apply `swcOptions` of the matched rules, later rules take precedence.
+  pub fn apply_swc_options(&self, resource_path: &str, config: &mut Config) {
+    for rule in &self.rules {
+      if rule.test.iter().any(|pattern| pattern.test(resource_path)) {
+        let mut overridden = rule.swc_options.clone();
+        overridden.merge(config.clone());
+        *config = overridden;
+      }
+    }
+  }
+}
@@ -1,33 +1,40 @@
 #![feature(let_chains)]
 
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::default::Default;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use serde::Deserialize;
 use swc_compiler::{IntoJsAst, SwcCompiler};
-use rspack_core::{Mode, RunnerContext};
-use rspack_cacheable::{cacheable, cacheable_dyn, with::{AsRefStrConverter, AsRefStr}};
+use rspack_core::{DependencyCategory, Mode, ResolveOptionsWithDependencyType, RunnerContext};
+use rspack_cacheable::{cacheable, cacheable_dyn, with::{AsRefStrConverter, AsRefStr, Skip}};
 use rspack_error::{error, AnyhowError, Diagnostic, Result};
 use rspack_loader_runner::{Identifiable, Identifier, Loader, LoaderContext};
-use rspack_regex::RspackRegex;
 use rspack_plugin_javascript::ast::{self, SourceMapConfig};
 use rspack_plugin_javascript::TransformOutput;
 use rspack_util::source_map::SourceMapKind;
 use swc_config::{config_types::MergingOption, merge::Merge};
 use swc_core::{
   base::config::{Config, InputSourceMap, Options, OutputCharset, TransformConfig, SourceMapsConfig},
-  ecma::parser::{Syntax, TsSyntax},
 };
 use swc_core::ecma::visit::VisitWith;
+use swc_keep_export::ExportSummary;
 
+mod cache;
+pub use cache::{CachedTransform, TransformCache, TransformCacheOptions};
+mod compile_rules;
+pub use compile_rules::{CompileRules, RawCompileRules};
+mod side_effects;
+use side_effects::{decided_side_effects, ImportSideEffects};
+pub use side_effects::{package_has_side_effects, SideEffectsConfig, SideEffectsOptions};
+mod plugin;
+pub use plugin::CompilationLoaderPlugin;
+mod syntax;
+pub use syntax::resolve_syntax;
 mod transform;
 use transform::*;
-
-#[derive(Debug, Default, Deserialize)]
-#[serde(rename_all = "camelCase", default)]
-pub struct CompileRules {
-  // Built-in rules to exclude files from compilation, such as react, react-dom, etc.
-  exclude: Option<Vec<String>>,
-}
+pub use transform::{get_routes_config, is_entry, normalize_path, RoutesConfig, RoutesOptions};
 
 #[derive(Debug, Deserialize)]
 pub struct LoaderOptions {
@@ -36,7 +43,14 @@ pub struct LoaderOptions {
   #[serde(rename = "transformFeatures")]
   transform_features: TransformFeatureOptions,
   #[serde(rename = "compileRules")]
-  compile_rules: CompileRules,
+  compile_rules: RawCompileRules,
+  #[serde(default)]
+  routes: RoutesOptions,
+  #[serde(rename = "transformCache", default)]
+  transform_cache: Option<TransformCacheOptions>,
+  // Emit what `keepExport` and `removeExport` pruned from each entry as a diagnostic.
+  #[serde(default)]
+  verbose: bool,
 }
 
 impl AsRefStrConverter for CompilationOptions {
@@ -54,8 +68,21 @@ impl TryFrom<&str> for CompilationOptions {
 
   fn try_from(s: &str) -> Result<Self, Self::Error> {
     let value: LoaderOptions = serde_json::from_str(s)?;
-    let transform_features = value.transform_features;
-    let compile_rules = value.compile_rules;
+    let mut transform_features = value.transform_features;
+    transform_features
+      .compile_env_config()
+      .map_err(serde::de::Error::custom)?;
+    transform_features
+      .compile_export_patterns()
+      .map_err(serde::de::Error::custom)?;
+    transform_features
+      .compile_side_effects_config()
+      .map_err(serde::de::Error::custom)?;
+    let compile_rules =
+      CompileRules::new(value.compile_rules).map_err(serde::de::Error::custom)?;
+    let routes = value.routes;
+    let transform_cache = value.transform_cache;
+    let verbose = value.verbose;
     Ok(CompilationOptions {
       raw_options: s.into(),
       swc_options: Options {
@@ -64,6 +91,9 @@ impl TryFrom<&str> for CompilationOptions {
       },
       transform_features,
       compile_rules,
+      routes,
+      transform_cache,
+      verbose,
     })
   }
 }
@@ -75,6 +105,9 @@ pub struct CompilationOptions {
   pub(crate) swc_options: Options,
   pub(crate) transform_features: TransformFeatureOptions,
   pub(crate) compile_rules: CompileRules,
+  pub(crate) routes: RoutesOptions,
+  pub(crate) transform_cache: Option<TransformCacheOptions>,
+  pub(crate) verbose: bool,
 }
 
 #[cacheable]
@@ -82,6 +115,10 @@ pub struct CompilationOptions {
 pub struct CompilationLoader {
   identifier: Identifier,
   loader_options: CompilationOptions,
+  // Transform cache on the intermediate filesystem of the compiler, missing when the loader
+  // is not created by `CompilationLoaderPlugin` or `transformCache` is not enabled.
+  #[cacheable(with=Skip)]
+  transform_cache: Option<Arc<TransformCache>>,
 }
 
 impl CompilationLoader {
@@ -90,16 +127,22 @@ impl CompilationLoader {
     Ok(Self {
       identifier: COMPILATION_LOADER_IDENTIFIER.into(),
       loader_options,
+      transform_cache: None,
     })
   }
 
+  pub(crate) fn with_transform_cache(mut self, transform_cache: Arc<TransformCache>) -> Self {
+    self.transform_cache = Some(transform_cache);
+    self
+  }
+
   pub fn with_identifier(mut self, identifier: Identifier) -> Self {
     assert!(identifier.starts_with(COMPILATION_LOADER_IDENTIFIER));
     self.identifier = identifier;
     self
   }
 
-  fn loader_impl(&self, loader_context: &mut LoaderContext<RunnerContext>) -> Result<()> {
+  async fn loader_impl(&self, loader_context: &mut LoaderContext<RunnerContext>) -> Result<()> {
     let resource_path = loader_context
       .resource_path()
       .map(|p| p.to_path_buf())
@@ -109,46 +152,39 @@ impl CompilationLoader {
       return Ok(());
     };
 
-    if self.loader_options.compile_rules.exclude.is_some() {
-      let exclude = self.loader_options.compile_rules.exclude.as_ref().unwrap();
-      for pattern in exclude {
-        let pattern = RspackRegex::new(pattern).unwrap();
-        if pattern.test(&resource_path.as_str()) {
-          loader_context.finish_with((content, None));
-          return Ok(());
-        }
-      }
+    let compile_rules = &self.loader_options.compile_rules;
+    if !compile_rules.should_compile(resource_path.as_str()) {
+      loader_context.finish_with((content, None));
+      return Ok(());
     }
 
+    let development = Mode::is_development(&loader_context.context.options.mode);
+    let input_source_map = loader_context
+      .source_map()
+      .and_then(|source_map| source_map.to_json().ok());
     let swc_options = {
       let mut swc_options = self.loader_options.swc_options.clone();
+      compile_rules.apply_swc_options(resource_path.as_str(), &mut swc_options.config);
       if swc_options.config.jsc.transform.as_ref().is_some() {
         let mut transform = TransformConfig::default();
-        transform.react.development =
-          Some(Mode::is_development(&loader_context.context.options.mode));
+        transform.react.development = Some(development);
         swc_options
           .config
           .jsc
           .transform
           .merge(MergingOption::from(Some(transform)));
       }
-      if let Some(pre_source_map) = loader_context.source_map().cloned() {
-        if let Ok(source_map) = pre_source_map.to_json() {
-          swc_options.config.input_source_map = Some(InputSourceMap::Str(source_map))
-        }
+      if let Some(source_map) = &input_source_map {
+        swc_options.config.input_source_map = Some(InputSourceMap::Str(source_map.clone()))
       }
       swc_options.filename = resource_path.as_str().to_string();
       swc_options.source_file_name = Some(resource_path.as_str().to_string());
 
-      let file_extension = resource_path.extension().unwrap();
-      let ts_extensions = vec!["tsx", "ts", "mts"];
-      if ts_extensions.iter().any(|ext| ext == &file_extension) {
-        swc_options.config.jsc.syntax = Some(Syntax::Typescript(TsSyntax {
-          tsx: true,
-          decorators: true,
-          ..Default::default()
-        }));
-      }
+      swc_options.config.jsc.syntax = resolve_syntax(
+        resource_path.as_std_path(),
+        loader_context.resource_query(),
+        swc_options.config.jsc.syntax,
+      );
       if swc_options.config.jsc.target.is_some() && swc_options.config.env.is_some() {
         loader_context.emit_diagnostic(Diagnostic::warn(
           COMPILATION_LOADER_IDENTIFIER.to_string(),
@@ -164,85 +200,233 @@ impl CompilationLoader {
     };
 
     let source = content.try_into_string()?;
-    let c = SwcCompiler::new(
-      resource_path.clone().into_std_path_buf(),
-      source.clone(),
-      swc_options,
-    )
-    .map_err(AnyhowError::from)?;
 
-    let mut routes_config: Option<Vec<String>> = None;
     let compiler_context: &str = loader_context.context.options.context.as_ref();
-    let routes_config_path: std::path::PathBuf =
-      Path::new(compiler_context).join(".ice/route-manifest.json");
-    let routes_content = load_routes_config(&routes_config_path);
-    if routes_content.is_ok() {
-      routes_config = Some(routes_content.map_err(AnyhowError::from)?);
+    let routes_config = get_routes_config(
+      Path::new(compiler_context),
+      &self.loader_options.routes,
+      loader_context.context.compilation_id,
+    );
+    // Rebuild the route modules when routes are added or removed in the route manifest.
+    if let Some(manifest) = &routes_config.manifest
+      && routes_config.is_route_candidate(resource_path.as_str())
+    {
+      loader_context.file_dependencies.insert(manifest.clone());
     }
 
-    let transform_options = &self.loader_options.transform_features;
-    let built = c
-      .parse(None, |_| {
-        transform(&resource_path.as_str(), routes_config.as_ref(), transform_options)
-      })
-      .map_err(AnyhowError::from)?;
-
-    let input_source_map = c
-      .input_source_map(&built.input_source_map)
-      .map_err(|e| error!(e.to_string()))?;
-    let mut codegen_options = ast::CodegenOptions {
-      target: Some(built.target),
-      minify: Some(built.minify),
-      input_source_map: input_source_map.as_ref(),
-      ascii_only: built
-        .output
-        .charset
-        .as_ref()
-        .map(|v| matches!(v, OutputCharset::Ascii)),
-      source_map_config: SourceMapConfig {
-        enable: source_map_kind.source_map(),
-        inline_sources_content: source_map_kind.source_map(),
-        emit_columns: !source_map_kind.cheap(),
-        names: Default::default(),
-      },
-      inline_script: Some(false),
-      keep_comments: Some(true),
+    let is_entry = is_entry(resource_path.as_str(), routes_config.files.as_deref());
+    let transform_cache = self.transform_cache.as_deref();
+    // Only the imports of entries are pruned, so their side effects are not needed otherwise.
+    let import_side_effects = match &self.loader_options.transform_features.side_effects_config {
+      Some(config) if is_entry => {
+        let resolver = loader_context
+          .context
+          .resolver_factory
+          .get(ResolveOptionsWithDependencyType {
+            resolve_options: None,
+            resolve_to_context: false,
+            dependency_category: DependencyCategory::Esm,
+          });
+        Some(ImportSideEffects::new(
+          config.clone(),
+          resolver,
+          resource_path.as_std_path(),
+        ))
+      }
+      _ => None,
     };
+    let cache_key = transform_cache.map(|_| {
+      // The swc config is built from the raw options, the resource and the input source map.
+      TransformCache::key(&[
+        source.as_bytes(),
+        self.loader_options.raw_options.as_bytes(),
+        resource_path.as_str().as_bytes(),
+        compiler_context.as_bytes(),
+        loader_context.resource_query().unwrap_or_default().as_bytes(),
+        input_source_map.as_deref().unwrap_or_default().as_bytes(),
+        &source_map_kind.bits().to_le_bytes(),
+        &[is_entry as u8, development as u8],
+      ])
+    });
+    if let Some(transform_cache) = transform_cache
+      && let Some(cache_key) = cache_key
+      && let Some(cached) = transform_cache.get(cache_key).await
+      // A cached output is only valid while the imports it pruned have the same side effects.
+      && cached.side_effects_unchanged(|src| {
+        import_side_effects
+          .as_ref()
+          .is_some_and(|side_effects| side_effects.has_side_effects(src))
+      })
+    {
+      if let Some(pruned_exports) = cached.pruned_exports {
+        if self.loader_options.verbose
+          && let Some(summary) = export_summary_from_json(&pruned_exports)
+        {
+          emit_export_summary(loader_context, resource_path.as_str(), &summary);
+        }
+        loader_context
+          .parse_meta
+          .insert(PRUNED_EXPORTS_META_KEY.to_string(), pruned_exports);
+      }
+      let TransformOutput { code, map } = cached.output;
+      loader_context.finish_with((code, map));
+      return Ok(());
+    }
 
-    let program = c.transform(built).map_err(AnyhowError::from)?;
-    if source_map_kind.enabled() {
-      let mut v = IdentCollector {
-        names: Default::default(),
-      };
-      program.visit_with(&mut v);
-      codegen_options.source_map_config.names = v.names;
+    let entry_exports = EntryExports {
+      development,
+      // Named by the throwing stub of a removed default export, so that the output does not
+      // include absolute paths of the machine it is built on.
+      relative_path: Path::new(resource_path.as_str())
+        .strip_prefix(compiler_context)
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| resource_path.to_string()),
+      ..Default::default()
+    };
+    let (output, side_effects) = grow_stack(|| {
+      transform_source(
+        resource_path.as_str(),
+        source,
+        swc_options,
+        source_map_kind,
+        is_entry,
+        &self.loader_options.transform_features,
+        &entry_exports,
+        import_side_effects.as_ref(),
+      )
+    })?;
+    let export_summary = entry_exports.summary();
+    if self.loader_options.verbose
+      && let Some(summary) = &export_summary
+    {
+      emit_export_summary(loader_context, resource_path.as_str(), summary);
     }
-    let ast = c.into_js_ast(program);
-    let TransformOutput { code, map } = ast::stringify(&ast, codegen_options)?;
+    let pruned_exports = export_summary.as_ref().map(export_summary_to_json);
+    if let Some(transform_cache) = transform_cache
+      && let Some(cache_key) = cache_key
+    {
+      transform_cache
+        .set(
+          cache_key,
+          &output,
+          pruned_exports.as_ref(),
+          side_effects,
+        )
+        .await;
+    }
+    if let Some(pruned_exports) = pruned_exports {
+      // Stored in `module.buildInfo.parseMeta` so plugins can tell what was pruned from entries.
+      loader_context
+        .parse_meta
+        .insert(PRUNED_EXPORTS_META_KEY.to_string(), pruned_exports);
+    }
+    let TransformOutput { code, map } = output;
     loader_context.finish_with((code, map));
     Ok(())
   }
 }
 
+// Reported as a warning so that it shows up in the stats, `verbose` is only enabled on purpose.
+fn emit_export_summary(
+  loader_context: &mut LoaderContext<RunnerContext>,
+  resource_path: &str,
+  summary: &ExportSummary,
+) {
+  loader_context.emit_diagnostic(Diagnostic::warn(
+    COMPILATION_LOADER_IDENTIFIER.to_string(),
+    format_export_summary(resource_path, summary),
+  ));
+}
+
+// Returns the output and the side effects decided for the imports of the module.
+#[allow(clippy::too_many_arguments)]
+fn transform_source(
+  resource_path: &str,
+  source: String,
+  swc_options: Options,
+  source_map_kind: SourceMapKind,
+  is_entry: bool,
+  transform_options: &TransformFeatureOptions,
+  entry_exports: &EntryExports,
+  import_side_effects: Option<&ImportSideEffects>,
+) -> Result<(TransformOutput, BTreeMap<String, bool>)> {
+  let c = SwcCompiler::new(PathBuf::from(resource_path), source, swc_options)
+    .map_err(AnyhowError::from)?;
+
+  let side_effects = RefCell::new(BTreeMap::new());
+  let built = c
+    .parse(None, |program| {
+      // Resolve the imports once before the transform, instead of while it visits them.
+      if let Some(import_side_effects) = import_side_effects {
+        *side_effects.borrow_mut() = import_side_effects.decide(program);
+      }
+      transform(
+        resource_path,
+        is_entry,
+        transform_options,
+        entry_exports,
+        decided_side_effects(side_effects.borrow().clone()),
+      )
+    })
+    .map_err(AnyhowError::from)?;
+
+  let input_source_map = c
+    .input_source_map(&built.input_source_map)
+    .map_err(|e| error!(e.to_string()))?;
+  let mut codegen_options = ast::CodegenOptions {
+    target: Some(built.target),
+    minify: Some(built.minify),
+    input_source_map: input_source_map.as_ref(),
+    ascii_only: built
+      .output
+      .charset
+      .as_ref()
+      .map(|v| matches!(v, OutputCharset::Ascii)),
+    source_map_config: SourceMapConfig {
+      enable: source_map_kind.source_map(),
+      inline_sources_content: source_map_kind.source_map(),
+      emit_columns: !source_map_kind.cheap(),
+      names: Default::default(),
+    },
+    inline_script: Some(false),
+    keep_comments: Some(true),
+  };
+
+  let program = c.transform(built).map_err(AnyhowError::from)?;
+  if source_map_kind.enabled() {
+    let mut v = IdentCollector {
+      names: Default::default(),
+    };
+    program.visit_with(&mut v);
+    codegen_options.source_map_config.names = v.names;
+  }
+  let ast = c.into_js_ast(program);
+  let output = ast::stringify(&ast, codegen_options)?;
+  Ok((output, side_effects.into_inner()))
+}
+
+fn grow_stack<R>(f: impl FnOnce() -> R) -> R {
+  #[cfg(debug_assertions)]
+  {
+    // Adjust stack to avoid stack overflow.
+    stacker::maybe_grow(
+      2 * 1024 * 1024, /* 2mb */
+      4 * 1024 * 1024, /* 4mb */
+      f,
+    )
+  }
+  #[cfg(not(debug_assertions))]
+  f()
+}
+
 pub const COMPILATION_LOADER_IDENTIFIER: &str = "builtin:compilation-loader";
+pub const PRUNED_EXPORTS_META_KEY: &str = "compilationLoader.prunedExports";
 
 #[cacheable_dyn]
 #[async_trait::async_trait]
 impl Loader<RunnerContext> for CompilationLoader {
   async fn run(&self, loader_context: &mut LoaderContext<RunnerContext>) -> Result<()> {
-    #[allow(unused_mut)]
-    let mut inner = || self.loader_impl(loader_context);
-    #[cfg(debug_assertions)]
-    {
-      // Adjust stack to avoid stack overflow.
-      stacker::maybe_grow(
-        2 * 1024 * 1024, /* 2mb */
-        4 * 1024 * 1024, /* 4mb */
-        inner,
-      )
-    }
-    #[cfg(not(debug_assertions))]
-    inner()
+    self.loader_impl(loader_context).await
   }
 }
 
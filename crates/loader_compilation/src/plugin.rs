@@ -0,0 +1,109 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::{Arc, Mutex},
+};
+
+use rspack_core::{
+  ApplyContext, BoxLoader, CompilerOptions, Context, ModuleRuleUseLoader,
+  NormalModuleFactoryResolveLoader, Plugin, PluginContext, Resolver,
+};
+use rspack_error::{error, Result};
+use rspack_fs::IntermediateFileSystem;
+use rspack_hook::{plugin, plugin_hook};
+use rspack_paths::{Utf8Path, Utf8PathBuf};
+use tokio::sync::RwLock;
+
+use crate::{CompilationLoader, TransformCache, COMPILATION_LOADER_IDENTIFIER};
+
+/// Creates `builtin:compilation-loader` with a transform cache on the intermediate filesystem
+/// of the compiler, when `transformCache` is enabled.
+///
+/// The plugin must be applied before `JsLoaderRspackPlugin`, which resolves builtin loaders
+/// without any compiler state.
+#[plugin]
+#[derive(Debug)]
+pub struct CompilationLoaderPlugin {
+  context: Utf8PathBuf,
+  intermediate_fs: Arc<dyn IntermediateFileSystem>,
+  // Loaders by request and options, so that the loader is not created for every module.
+  loaders: RwLock<HashMap<(String, String), Arc<CompilationLoader>>>,
+  // Cache directories whose stale entries are already removed.
+  evicted_directories: Mutex<HashSet<Utf8PathBuf>>,
+}
+
+impl CompilationLoaderPlugin {
+  pub fn new(
+    compiler_options: &CompilerOptions,
+    intermediate_fs: Arc<dyn IntermediateFileSystem>,
+  ) -> Self {
+    let context: &str = compiler_options.context.as_ref();
+    Self::new_inner(
+      Utf8Path::new(context).to_path_buf(),
+      intermediate_fs,
+      Default::default(),
+      Default::default(),
+    )
+  }
+
+  fn create_loader(&self, request: &str, options: &str) -> Result<CompilationLoader> {
+    let loader = CompilationLoader::new(options)
+      .map_err(|e| error!("failed to parse builtin:compilation-loader options: {e}"))?
+      .with_identifier(request.into());
+    let Some(options) = &loader.loader_options.transform_cache else {
+      return Ok(loader);
+    };
+    let transform_cache = Arc::new(TransformCache::new(
+      self.intermediate_fs.clone(),
+      &self.context,
+      options,
+    ));
+    // Stale entries are removed in the background, so that the first modules are not delayed.
+    if self
+      .evicted_directories
+      .lock()
+      .expect("failed to lock evicted directories")
+      .insert(transform_cache.directory().to_path_buf())
+    {
+      let transform_cache = transform_cache.clone();
+      tokio::spawn(async move { transform_cache.evict_stale_entries().await });
+    }
+    Ok(loader.with_transform_cache(transform_cache))
+  }
+}
+
+#[plugin_hook(NormalModuleFactoryResolveLoader for CompilationLoaderPlugin)]
+async fn resolve_loader(
+  &self,
+  _context: &Context,
+  _resolver: &Resolver,
+  l: &ModuleRuleUseLoader,
+) -> Result<Option<BoxLoader>> {
+  if !l.loader.starts_with(COMPILATION_LOADER_IDENTIFIER) {
+    return Ok(None);
+  }
+  let key = (
+    l.loader.clone(),
+    l.options.clone().unwrap_or_else(|| "{}".to_string()),
+  );
+  if let Some(loader) = self.loaders.read().await.get(&key) {
+    return Ok(Some(loader.clone()));
+  }
+  let loader = Arc::new(self.create_loader(&key.0, &key.1)?);
+  self.loaders.write().await.insert(key, loader.clone());
+  Ok(Some(loader))
+}
+
+impl Plugin for CompilationLoaderPlugin {
+  fn name(&self) -> &'static str {
+    "CompilationLoaderPlugin"
+  }
+
+  fn apply(&self, ctx: PluginContext<&mut ApplyContext>, _options: &CompilerOptions) -> Result<()> {
+    ctx
+      .context
+      .normal_module_factory_hooks
+      .resolve_loader
+      .tap(resolve_loader::new(self));
+    Ok(())
+  }
+}
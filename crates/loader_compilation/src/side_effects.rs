@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use rspack_core::{ResolveResult, Resolver};
+use serde::Deserialize;
+use serde_json::Value;
+use swc_core::ecma::ast::{ModuleDecl, ModuleItem, Program};
+use swc_keep_export::SideEffects;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SideEffectsOptions {
+  // Globs of import sources or resolved module paths which have side effects.
+  pub include: Option<Vec<String>>,
+  // Read the `sideEffects` field in package.json of the imported modules in node_modules,
+  // packages without the field are treated as having side effects like webpack does.
+  // Modules of the app itself only have side effects when matched by `include`.
+  pub package_json: bool,
+}
+
+/// Side effects options compiled when the loader options are parsed.
+#[derive(Debug)]
+pub struct SideEffectsConfig {
+  include: Vec<glob::Pattern>,
+  package_json: bool,
+}
+
+impl SideEffectsConfig {
+  pub fn new(options: &SideEffectsOptions) -> Result<Self, String> {
+    let include = options
+      .include
+      .iter()
+      .flatten()
+      .map(|pattern| {
+        glob::Pattern::new(pattern)
+          .map_err(|e| format!("invalid glob `{pattern}` in sideEffects.include: {e}"))
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(Self {
+      include,
+      package_json: options.package_json,
+    })
+  }
+
+  fn is_included(&self, path: &str) -> bool {
+    self.include.iter().any(|pattern| pattern.matches(path))
+  }
+
+  /// Whether the resolved module at `module_path` has side effects, `package` is the
+  /// directory and the content of the package.json describing it.
+  pub fn module_has_side_effects(
+    &self,
+    module_path: &Path,
+    package: Option<(&Path, &Value)>,
+  ) -> bool {
+    if self.is_included(&module_path.to_string_lossy()) {
+      return true;
+    }
+    let in_node_modules = module_path
+      .components()
+      .any(|component| component == Component::Normal("node_modules".as_ref()));
+    self.package_json
+      && in_node_modules
+      && package.is_some_and(|(package_dir, package_json)| {
+        package_has_side_effects(package_dir, package_json, module_path)
+      })
+  }
+}
+
+/// Decide whether the imports of a module have side effects. Import sources are resolved once
+/// before the transform, see [ImportSideEffects::decide].
+pub(crate) struct ImportSideEffects {
+  config: Arc<SideEffectsConfig>,
+  resolver: Arc<Resolver>,
+  context: PathBuf,
+}
+
+impl ImportSideEffects {
+  pub(crate) fn new(
+    config: Arc<SideEffectsConfig>,
+    resolver: Arc<Resolver>,
+    resource_path: &Path,
+  ) -> Self {
+    let context = resource_path
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or_default();
+    Self {
+      config,
+      resolver,
+      context,
+    }
+  }
+
+  /// Import sources are resolved only when they are not matched by `include` directly.
+  pub(crate) fn has_side_effects(&self, src: &str) -> bool {
+    if self.config.is_included(src) {
+      return true;
+    }
+    let Ok(ResolveResult::Resource(resource)) = self.resolver.resolve(&self.context, src) else {
+      return false;
+    };
+    let package = resource
+      .description_data
+      .as_ref()
+      .map(|data| (data.path(), data.json()));
+    self
+      .config
+      .module_has_side_effects(resource.path.as_std_path(), package)
+  }
+
+  /// Decide the side effects of every import of `program`, so that the transform
+  /// only looks them up.
+  pub(crate) fn decide(&self, program: &Program) -> BTreeMap<String, bool> {
+    let Program::Module(module) = program else {
+      return BTreeMap::new();
+    };
+    module
+      .body
+      .iter()
+      .filter_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => {
+          Some(import.src.value.to_string())
+        }
+        _ => None,
+      })
+      .collect::<BTreeSet<_>>()
+      .into_iter()
+      .map(|src| {
+        let has_side_effects = self.has_side_effects(&src);
+        (src, has_side_effects)
+      })
+      .collect()
+  }
+}
+
+/// Side effects decided before the transform, imports not decided have no side effects.
+pub(crate) fn decided_side_effects(decisions: BTreeMap<String, bool>) -> SideEffects {
+  SideEffects::new(move |src| decisions.get(src).copied().unwrap_or(false))
+}
+
+/// Follows the `sideEffects` field of webpack, patterns without `/` match files in any directory.
+/// Modules of packages without the field are considered to have side effects.
+pub fn package_has_side_effects(package_dir: &Path, package_json: &Value, module_path: &Path) -> bool {
+  let matches = |pattern: &str| {
+    let pattern = pattern.trim_start_matches("./");
+    let pattern = if pattern.contains('/') {
+      pattern.to_string()
+    } else {
+      format!("**/{pattern}")
+    };
+    let Ok(relative) = module_path.strip_prefix(package_dir) else {
+      return false;
+    };
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    glob::Pattern::new(&pattern).is_ok_and(|glob| glob.matches(&relative))
+  };
+  match package_json.get("sideEffects") {
+    Some(Value::Bool(side_effects)) => *side_effects,
+    Some(Value::String(pattern)) => matches(pattern.as_str()),
+    Some(Value::Array(patterns)) => patterns
+      .iter()
+      .filter_map(Value::as_str)
+      .any(matches),
+    None => true,
+    _ => false,
+  }
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use swc_core::ecma::parser::{EsSyntax, Syntax, TsSyntax};
+
+/// Resolve the parser syntax of a module from its extension.
+///
+/// Modules without an extension, such as virtual modules, use the `lang` resource query
+/// (`?lang=tsx`), and then the syntax configured in `swcOptions`.
+pub fn resolve_syntax(
+  resource_path: &Path,
+  resource_query: Option<&str>,
+  configured: Option<Syntax>,
+) -> Option<Syntax> {
+  let lang = match resource_path.extension().and_then(|ext| ext.to_str()) {
+    Some(ext) => Some(ext),
+    None => resource_query.and_then(get_query_lang),
+  };
+  match lang {
+    Some("tsx") => Some(Syntax::Typescript(TsSyntax {
+      tsx: true,
+      decorators: true,
+      ..Default::default()
+    })),
+    // `<T>value` is a type assertion rather than JSX in `.ts` files.
+    Some(ext @ ("ts" | "mts" | "cts")) => Some(Syntax::Typescript(TsSyntax {
+      tsx: false,
+      decorators: true,
+      disallow_ambiguous_jsx_like: ext != "ts",
+      ..Default::default()
+    })),
+    Some("jsx") => match configured {
+      Some(Syntax::Es(es)) => Some(Syntax::Es(EsSyntax { jsx: true, ..es })),
+      _ => Some(Syntax::Es(EsSyntax {
+        jsx: true,
+        ..Default::default()
+      })),
+    },
+    _ => configured,
+  }
+}
+
+fn get_query_lang(query: &str) -> Option<&str> {
+  query
+    .trim_start_matches('?')
+    .split('&')
+    .find_map(|pair| pair.strip_prefix("lang="))
+}
@@ -1,18 +1,24 @@
-use std::path::Path;
-use anyhow::{Context, Error};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use anyhow::Error;
 use either::Either;
-use serde::Deserialize;
+use rspack_core::CompilationId;
+use serde::{Deserialize, Serialize};
 use swc_core::atoms::Atom;
 use swc_core::common::collections::AHashMap;
 use swc_core::common::BytePos;
 use swc_core::ecma::ast::{Ident, Pass, noop_pass};
 use swc_core::ecma::visit::{noop_visit_type, Visit};
-use swc_env_replacement::env_replacement;
-use swc_keep_export::keep_export;
+use swc_env_replacement::{env_replacement, parse_env_flag, EnvReplacementConfig};
+use swc_keep_export::{keep_export, ExportPattern, ExportReport, ExportSummary, SideEffects};
 use swc_named_import_transform::{named_import_transform, TransformConfig};
-use swc_remove_export::remove_export;
+use swc_remove_export::{remove_export, DefaultExportStub};
+use xxhash_rust::xxh3::xxh3_64;
 use swc_change_package_import::{change_package_import, Config as ImportConfig, SpecificConfigs};
 
+use crate::side_effects::{SideEffectsConfig, SideEffectsOptions};
+
 macro_rules! either {
   ($config:expr, $f:expr) => {
     if let Some(config) = &$config {
@@ -31,6 +37,20 @@ macro_rules! either {
   };
 }
 
+const DEFAULT_ROUTES_MANIFEST: &str = ".ice/route-manifest.json";
+const DEFAULT_ROUTES_ROOT: &str = "src/pages";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RoutesOptions {
+  // Path of the route manifest, relative to the compiler context.
+  pub manifest: Option<String>,
+  // Directory which route files are relative to, relative to the compiler context.
+  pub root: Option<String>,
+  // Route files relative to the route root, the route manifest is not read when provided.
+  pub files: Option<Vec<String>>,
+}
+
 // Only define the stuct which is used in the following function.
 #[derive(Deserialize, Debug)]
 struct NestedRoutesManifest {
@@ -38,40 +58,161 @@ struct NestedRoutesManifest {
   children: Option<Vec<NestedRoutesManifest>>,
 }
 
-fn get_routes_file(routes: Vec<NestedRoutesManifest>) -> Vec<String> {
-  let mut result: Vec<String> = vec![];
+fn get_routes_file(routes: Vec<NestedRoutesManifest>, routes_root: &Path) -> Vec<PathBuf> {
+  let mut result: Vec<PathBuf> = vec![];
   for route in routes {
-    // Add default prefix of src/pages/ to the route file.
-    let mut path_str = String::from("src/pages/");
-    path_str.push_str(&route.file);
-
-    result.push(path_str.to_string());
+    result.push(normalize_path(&routes_root.join(&route.file)));
 
     if let Some(children) = route.children {
-      result.append(&mut get_routes_file(children));
+      result.append(&mut get_routes_file(children, routes_root));
     }
   }
   result
 }
 
-fn parse_routes_config(c: String) -> Result<Vec<String>, Error> {
-  let routes = serde_json::from_str(&c)?;
-  Ok(get_routes_file(routes))
+fn parse_routes_config(c: &[u8], routes_root: &Path) -> Result<Vec<PathBuf>, Error> {
+  let routes = serde_json::from_slice(c)?;
+  Ok(get_routes_file(routes, routes_root))
 }
 
-pub(crate) fn load_routes_config(path: &Path) -> Result<Vec<String>, Error> {
-  let content = std::fs::read_to_string(path).context("failed to read routes config")?;
-  parse_routes_config(content)
+/// Route files of the project and where they come from.
+#[derive(Debug)]
+pub struct RoutesConfig {
+  // Absolute paths of the route files.
+  pub files: Option<Arc<Vec<PathBuf>>>,
+  pub root: PathBuf,
+  // The route manifest which the route files are read from.
+  pub manifest: Option<PathBuf>,
 }
 
-fn match_route_entry(resource_path: &str, routes: Option<&Vec<String>>) -> bool {
-  if let Some(routes) = routes {
-    for route in routes {
-      if resource_path.ends_with(&route.to_string()) {
-        return true;
+impl RoutesConfig {
+  /// Modules under the route root may become route entries when the route manifest changes.
+  pub fn is_route_candidate(&self, resource_path: &str) -> bool {
+    normalize_path(Path::new(resource_path)).starts_with(&self.root)
+  }
+}
+
+#[derive(Debug)]
+struct RoutesManifestCacheEntry {
+  compilation_id: CompilationId,
+  // Loaded by the first module of the compilation, outside of the cache lock.
+  manifest: Arc<OnceLock<LoadedRoutesManifest>>,
+}
+
+#[derive(Debug)]
+struct LoadedRoutesManifest {
+  // Hash of the manifest content, as edits within the same second keep the mtime.
+  content_hash: Option<u64>,
+  files: Option<Arc<Vec<PathBuf>>>,
+}
+
+// Parsed route manifests keyed by (manifest, route root), shared by every module of a compilation.
+static ROUTES_MANIFEST_CACHE: LazyLock<
+  Mutex<HashMap<(PathBuf, PathBuf), RoutesManifestCacheEntry>>,
+> = LazyLock::new(Default::default);
+
+fn load_routes_manifest(
+  manifest: &Path,
+  routes_root: &Path,
+  compilation_id: CompilationId,
+) -> Option<Arc<Vec<PathBuf>>> {
+  let (loaded, previous) = {
+    let mut cache = ROUTES_MANIFEST_CACHE
+      .lock()
+      .expect("failed to lock routes manifest cache");
+    let key = (manifest.to_path_buf(), routes_root.to_path_buf());
+    match cache.get(&key) {
+      Some(entry) if entry.compilation_id == compilation_id => (entry.manifest.clone(), None),
+      _ => {
+        let loaded = Arc::new(OnceLock::new());
+        let previous = cache.insert(
+          key,
+          RoutesManifestCacheEntry {
+            compilation_id,
+            manifest: loaded.clone(),
+          },
+        );
+        (loaded, previous)
+      }
+    }
+  };
+  loaded
+    .get_or_init(|| {
+      let content = std::fs::read(manifest).ok();
+      let content_hash = content.as_deref().map(xxh3_64);
+      // Only parse the manifest again when it has changed since the last compilation.
+      if let Some(previous) = previous.as_ref().and_then(|entry| entry.manifest.get())
+        && previous.content_hash == content_hash
+      {
+        return LoadedRoutesManifest {
+          content_hash,
+          files: previous.files.clone(),
+        };
+      }
+      let files = content
+        .and_then(|content| parse_routes_config(&content, routes_root).ok())
+        .map(Arc::new);
+      LoadedRoutesManifest {
+        content_hash,
+        files,
+      }
+    })
+    .files
+    .clone()
+}
+
+/// Get absolute paths of the route files, from the inline list or the route manifest.
+pub fn get_routes_config(
+  context: &Path,
+  options: &RoutesOptions,
+  compilation_id: CompilationId,
+) -> RoutesConfig {
+  let routes_root =
+    normalize_path(&context.join(options.root.as_deref().unwrap_or(DEFAULT_ROUTES_ROOT)));
+  if let Some(files) = &options.files {
+    return RoutesConfig {
+      files: Some(Arc::new(
+        files
+          .iter()
+          .map(|file| normalize_path(&routes_root.join(file)))
+          .collect(),
+      )),
+      root: routes_root,
+      manifest: None,
+    };
+  }
+  let manifest = normalize_path(
+    &context.join(options.manifest.as_deref().unwrap_or(DEFAULT_ROUTES_MANIFEST)),
+  );
+  RoutesConfig {
+    files: load_routes_manifest(&manifest, &routes_root, compilation_id),
+    root: routes_root,
+    manifest: Some(manifest),
+  }
+}
+
+// Resolve `.` and `..` without touching the file system, so paths can be compared directly.
+pub fn normalize_path(path: &Path) -> PathBuf {
+  let mut result = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !result.pop() {
+          result.push(component);
+        }
       }
+      _ => result.push(component),
     }
   }
+  result
+}
+
+fn match_route_entry(resource_path: &str, routes: Option<&Vec<PathBuf>>) -> bool {
+  if let Some(routes) = routes {
+    let resource_path = normalize_path(Path::new(resource_path));
+    return routes.iter().any(|route| *route == resource_path);
+  }
   false
 }
 
@@ -81,19 +222,192 @@ fn match_app_entry(resource_path: &str) -> bool {
   regex_for_app.is_match(resource_path)
 }
 
+/// App entry and route entries are transformed by `keep_export` and `remove_export`.
+pub fn is_entry(resource_path: &str, routes: Option<&Vec<PathBuf>>) -> bool {
+  match_app_entry(resource_path) || match_route_entry(resource_path, routes)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EnvReplacementOptions {
+  // Packages whose imports are replaced, defaults to `@uni/env` and `universal-env`.
+  pub sources: Option<Vec<String>>,
+  // Map of flag name to the expression it is replaced with.
+  pub flags: Option<HashMap<String, String>>,
+  // Renderer and target of the current build, such as `client` and `web`,
+  // env flags are replaced with boolean literals when provided.
+  pub renderer: Option<String>,
+  pub target: Option<String>,
+}
+
+// Replacement of the default export removed by `removeExport`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DefaultExportStubOption {
+  EmptyFn,
+  Throw,
+  Undefined,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct TransformFeatureOptions {
+  // Export names, globs such as `getConfig*` or regexes such as `/^\w+DataLoader$/`.
   pub keep_export: Option<Vec<String>>,
   pub remove_export: Option<Vec<String>>,
+  // Defaults to `throw` in development and `emptyFn` otherwise.
+  pub default_export_stub: Option<DefaultExportStubOption>,
   pub optimize_import: Option<Vec<String>>,
   pub import_config: Option<Vec<SpecificConfigs>>,
+  pub env_replacement: Option<EnvReplacementOptions>,
+  // Keep bare imports of modules with side effects when `keep_export` or `remove_export`
+  // prune all of their specifiers.
+  pub side_effects: Option<SideEffectsOptions>,
+  // Compiled from `env_replacement` when the loader options are parsed.
+  #[serde(skip)]
+  pub env_config: EnvReplacementConfig,
+  // Compiled from `keep_export` and `remove_export` when the loader options are parsed.
+  #[serde(skip)]
+  pub keep_export_patterns: Option<Vec<ExportPattern>>,
+  #[serde(skip)]
+  pub remove_export_patterns: Option<Vec<ExportPattern>>,
+  #[serde(skip)]
+  pub side_effects_config: Option<Arc<SideEffectsConfig>>,
+}
+
+impl TransformFeatureOptions {
+  pub(crate) fn compile_env_config(&mut self) -> Result<(), String> {
+    let Some(options) = &self.env_replacement else {
+      return Ok(());
+    };
+    if let Some(sources) = &options.sources {
+      self.env_config.sources = sources.clone();
+    }
+    self.env_config.renderer = options.renderer.clone();
+    self.env_config.target = options.target.clone();
+    if let Some(flags) = &options.flags {
+      for (name, expr) in flags {
+        self
+          .env_config
+          .flags
+          .insert(name.clone(), parse_env_flag(name, expr)?);
+      }
+    }
+    Ok(())
+  }
+
+  pub(crate) fn compile_export_patterns(&mut self) -> Result<(), String> {
+    fn compile(patterns: &Option<Vec<String>>) -> Result<Option<Vec<ExportPattern>>, String> {
+      patterns
+        .as_ref()
+        .map(|patterns| patterns.iter().map(|p| ExportPattern::parse(p)).collect())
+        .transpose()
+    }
+    self.keep_export_patterns = compile(&self.keep_export)?;
+    self.remove_export_patterns = compile(&self.remove_export)?;
+    Ok(())
+  }
+
+  pub(crate) fn compile_side_effects_config(&mut self) -> Result<(), String> {
+    if let Some(options) = &self.side_effects {
+      self.side_effects_config = Some(Arc::new(SideEffectsConfig::new(options)?));
+    }
+    Ok(())
+  }
+}
+
+/// Inputs of `keep_export` and `remove_export` for an entry, and what they pruned.
+#[derive(Debug, Default)]
+pub(crate) struct EntryExports {
+  pub keep_export: ExportReport,
+  pub remove_export: ExportReport,
+  pub development: bool,
+  // Path of the entry relative to the compiler context.
+  pub relative_path: String,
+}
+
+impl EntryExports {
+  /// What `keep_export` and `remove_export` pruned, `None` if the entry is not pruned.
+  pub(crate) fn summary(&self) -> Option<ExportSummary> {
+    let mut summary = self.keep_export.summary();
+    summary.merge(self.remove_export.summary());
+    (!summary.is_empty()).then_some(summary)
+  }
+}
+
+// Format of the pruned exports in `module.buildInfo.parseMeta` and the transform cache.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportSummaryJson {
+  kept: BTreeSet<String>,
+  removed: BTreeSet<String>,
+  #[serde(default)]
+  export_all: BTreeMap<String, bool>,
+  dropped_imports: BTreeMap<String, BTreeSet<String>>,
+  side_effect_imports: BTreeSet<String>,
+  removed_declarations: BTreeSet<String>,
+}
+
+pub(crate) fn export_summary_to_json(summary: &ExportSummary) -> String {
+  let summary = ExportSummaryJson {
+    kept: summary.kept.clone(),
+    removed: summary.removed.clone(),
+    export_all: summary.export_all.clone(),
+    dropped_imports: summary.dropped_imports.clone(),
+    side_effect_imports: summary.side_effect_imports.clone(),
+    removed_declarations: summary.removed_declarations.clone(),
+  };
+  serde_json::to_string(&summary).expect("failed to serialize export summary")
+}
+
+pub(crate) fn export_summary_from_json(json: &str) -> Option<ExportSummary> {
+  let summary: ExportSummaryJson = serde_json::from_str(json).ok()?;
+  Some(ExportSummary {
+    kept: summary.kept,
+    removed: summary.removed,
+    export_all: summary.export_all,
+    dropped_imports: summary.dropped_imports,
+    side_effect_imports: summary.side_effect_imports,
+    removed_declarations: summary.removed_declarations,
+  })
+}
+
+pub(crate) fn format_export_summary(resource_path: &str, summary: &ExportSummary) -> String {
+  fn join<'a>(names: impl IntoIterator<Item = &'a String>) -> String {
+    names.into_iter().map(String::as_str).collect::<Vec<_>>().join(", ")
+  }
+  let dropped_imports = summary
+    .dropped_imports
+    .iter()
+    .map(|(src, locals)| format!("{} from '{src}'", join(locals)))
+    .collect::<Vec<_>>()
+    .join("; ");
+  let export_all = |kept: bool| {
+    summary
+      .export_all
+      .iter()
+      .filter(|(_, export_all_kept)| **export_all_kept == kept)
+      .map(|(src, _)| format!("'{src}'"))
+      .collect::<Vec<_>>()
+      .join(", ")
+  };
+  format!(
+    "Pruned exports of {resource_path}\n  kept exports: {}\n  removed exports: {}\n  kept `export *` from: {}\n  removed `export *` from: {}\n  dropped imports: {dropped_imports}\n  side-effect imports kept: {}\n  removed declarations: {}",
+    join(&summary.kept),
+    join(&summary.removed),
+    export_all(true),
+    export_all(false),
+    join(&summary.side_effect_imports),
+    join(&summary.removed_declarations),
+  )
 }
 
 pub(crate) fn transform<'a>(
   resource_path: &'a str,
-  routes_config: Option<&Vec<String>>,
+  is_entry: bool,
   feature_options: &TransformFeatureOptions,
+  entry_exports: &EntryExports,
+  side_effects: SideEffects,
 ) -> impl Pass + 'a {
   (
     either!(feature_options.optimize_import, |options: &Vec<String>| {
@@ -109,29 +423,54 @@ pub(crate) fn transform<'a>(
       }
     ),
     either!(
-      Some(&vec!["@uni/env".to_string(), "universal-env".to_string()]),
-      |options: &Vec<String>| { env_replacement(options.clone()) }
+      Some(&feature_options.env_config),
+      |config: &EnvReplacementConfig| { env_replacement(config.clone()) }
     ),
     either!(
-      feature_options.keep_export,
-      |options: &Vec<String>| {
-        let mut exports_name = options.clone();
+      feature_options.keep_export_patterns,
+      |patterns: &Vec<ExportPattern>| {
+        let mut exports_name = patterns.clone();
         // Special case for app entry.
         // When keep pageConfig, we should also keep the default export of app entry.
-        if match_app_entry(resource_path) && exports_name.contains(&String::from("pageConfig")) {
-          exports_name.push(String::from("default"));
+        if match_app_entry(resource_path)
+          && exports_name.iter().any(|pattern| pattern.matches("pageConfig"))
+        {
+          exports_name.push(ExportPattern::from("default"));
         }
-        keep_export(exports_name)
+        keep_export(
+          exports_name,
+          entry_exports.keep_export.clone(),
+          side_effects.clone(),
+        )
       },
-      || { match_app_entry(resource_path) || match_route_entry(resource_path, routes_config) }
+      || is_entry
     ),
     either!(
-      feature_options.remove_export,
-      |options: &Vec<String>| { remove_export(options.clone()) },
-      || {
-        // Remove export only work for app entry and route entry.
-        match_app_entry(resource_path) || match_route_entry(resource_path, routes_config)
-      }
+      feature_options.remove_export_patterns,
+      |patterns: &Vec<ExportPattern>| {
+        // Fail loudly in development when a removed default export, such as a server-only
+        // component, is used by mistake.
+        let stub = feature_options.default_export_stub.unwrap_or(if entry_exports.development {
+          DefaultExportStubOption::Throw
+        } else {
+          DefaultExportStubOption::EmptyFn
+        });
+        let default_stub = match stub {
+          DefaultExportStubOption::EmptyFn => DefaultExportStub::EmptyFn,
+          DefaultExportStubOption::Throw => DefaultExportStub::Throw {
+            filename: entry_exports.relative_path.clone(),
+          },
+          DefaultExportStubOption::Undefined => DefaultExportStub::Undefined,
+        };
+        remove_export(
+          patterns.clone(),
+          entry_exports.remove_export.clone(),
+          default_stub,
+          side_effects.clone(),
+        )
+      },
+      // Remove export only work for app entry and route entry.
+      || is_entry
     ),
   )
 }
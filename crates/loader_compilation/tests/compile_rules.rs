@@ -0,0 +1,66 @@
+use loader_compilation::{CompileRules, RawCompileRules};
+use serde_json::json;
+use swc_core::base::config::Config;
+use swc_core::ecma::ast::EsVersion;
+
+fn compile_rules(raw: serde_json::Value) -> Result<CompileRules, String> {
+  CompileRules::new(serde_json::from_value::<RawCompileRules>(raw).unwrap())
+}
+
+#[test]
+fn test_should_compile() {
+  let rules = compile_rules(json!({
+    "include": ["/src/", { "glob": "**/packages/*/index.js" }],
+    "exclude": [{ "glob": "**/src/vendor/**" }],
+  }))
+  .unwrap();
+  assert!(rules.should_compile("/app/src/index.js"));
+  assert!(rules.should_compile("/app/packages/ui/index.js"));
+  assert!(!rules.should_compile("/app/lib/index.js"));
+  // Excluded files are never compiled, even if they are included.
+  assert!(!rules.should_compile("/app/src/vendor/react.js"));
+}
+
+#[test]
+fn test_compile_all_without_include() {
+  let rules = compile_rules(json!({ "exclude": ["node_modules"] })).unwrap();
+  assert!(rules.should_compile("/app/src/index.js"));
+  assert!(!rules.should_compile("/app/node_modules/react/index.js"));
+}
+
+#[test]
+fn test_apply_swc_options_precedence() {
+  let rules = compile_rules(json!({
+    "rules": [
+      { "test": ["\\.ts$"], "swcOptions": { "jsc": { "target": "es2017" } } },
+      { "test": [{ "glob": "**/legacy/*.ts" }], "swcOptions": { "jsc": { "target": "es5" } } },
+    ],
+  }))
+  .unwrap();
+  let target = |resource_path: &str| {
+    let mut config: Config =
+      serde_json::from_value(json!({ "jsc": { "target": "es2015" } })).unwrap();
+    rules.apply_swc_options(resource_path, &mut config);
+    config.jsc.target
+  };
+  // Matched rules override the loader options, and later rules override earlier ones.
+  assert_eq!(target("/app/src/index.js"), Some(EsVersion::Es2015));
+  assert_eq!(target("/app/src/index.ts"), Some(EsVersion::Es2017));
+  assert_eq!(target("/app/src/legacy/index.ts"), Some(EsVersion::Es5));
+}
+
+#[test]
+fn test_invalid_pattern() {
+  let err = compile_rules(json!({
+    "rules": [
+      { "test": ["\\.ts$"], "swcOptions": {} },
+      { "test": ["\\.js$", "("], "swcOptions": {} },
+    ],
+  }))
+  .unwrap_err();
+  assert!(err.contains("compileRules.rules[1].test[1]"), "{err}");
+  assert!(err.contains("`(`"), "{err}");
+
+  let err = compile_rules(json!({ "include": [{ "glob": "[" }] })).unwrap_err();
+  assert!(err.contains("compileRules.include[0]"), "{err}");
+}
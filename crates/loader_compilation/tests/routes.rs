@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use loader_compilation::{get_routes_config, is_entry, normalize_path, RoutesOptions};
+use rspack_core::CompilationId;
+use serde_json::json;
+
+// Every test works in its own context so that tests can run in parallel.
+fn create_context(name: &str) -> PathBuf {
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_nanos();
+  let context = std::env::temp_dir().join(format!(
+    "loader_compilation_routes_{name}_{}_{nanos}",
+    std::process::id()
+  ));
+  std::fs::create_dir_all(&context).unwrap();
+  context
+}
+
+fn routes_options(options: serde_json::Value) -> RoutesOptions {
+  serde_json::from_value(options).unwrap()
+}
+
+#[test]
+fn test_routes_options() {
+  let options = routes_options(json!({
+    "manifest": "config/routes.json",
+    "root": "app/routes",
+    "files": ["index.tsx"],
+  }));
+  assert_eq!(options.manifest.as_deref(), Some("config/routes.json"));
+  assert_eq!(options.root.as_deref(), Some("app/routes"));
+  assert_eq!(options.files, Some(vec!["index.tsx".to_string()]));
+
+  let options = routes_options(json!({}));
+  assert!(options.manifest.is_none() && options.root.is_none() && options.files.is_none());
+}
+
+#[test]
+fn test_normalize_path() {
+  assert_eq!(
+    normalize_path(Path::new("/app/src/pages/./home/index.tsx")),
+    PathBuf::from("/app/src/pages/home/index.tsx")
+  );
+  assert_eq!(
+    normalize_path(Path::new("/app/src/pages/../layouts/./index.tsx")),
+    PathBuf::from("/app/src/layouts/index.tsx")
+  );
+  // Leading `..` of relative paths can not be resolved, so they are kept.
+  assert_eq!(
+    normalize_path(Path::new("../pages/../index.tsx")),
+    PathBuf::from("../index.tsx")
+  );
+}
+
+#[cfg(windows)]
+#[test]
+fn test_normalize_path_mixed_separators() {
+  assert_eq!(
+    normalize_path(Path::new("C:\\app\\src/pages\\./home/index.tsx")),
+    PathBuf::from("C:\\app\\src\\pages\\home\\index.tsx")
+  );
+}
+
+#[test]
+fn test_inline_route_files() {
+  let options = routes_options(json!({
+    "root": "./src/pages",
+    "files": ["./index.tsx", "blog/./index.tsx", "../layouts/index.tsx"],
+  }));
+  let routes = get_routes_config(Path::new("/app"), &options, CompilationId::new());
+  assert_eq!(
+    routes.files.as_deref(),
+    Some(&vec![
+      PathBuf::from("/app/src/pages/index.tsx"),
+      PathBuf::from("/app/src/pages/blog/index.tsx"),
+      PathBuf::from("/app/src/layouts/index.tsx"),
+    ])
+  );
+  // Inline route files do not read the route manifest.
+  assert!(routes.manifest.is_none());
+}
+
+#[test]
+fn test_match_route_entry() {
+  let options = routes_options(json!({ "files": ["index.tsx", "blog/index.tsx"] }));
+  let routes = get_routes_config(Path::new("/app"), &options, CompilationId::new());
+  let routes = routes.files.as_deref();
+  assert!(is_entry("/app/src/pages/index.tsx", routes));
+  assert!(is_entry("/app/src/pages/./blog/../blog/index.tsx", routes));
+  // `ends_with("index.tsx")` would match nested modules and modules of other projects.
+  assert!(!is_entry("/app/src/pages/about/index.tsx", routes));
+  assert!(!is_entry(
+    "/app/src/pages/blog/components/index.tsx",
+    routes
+  ));
+  assert!(!is_entry("/other/src/pages/index.tsx", routes));
+  assert!(!is_entry("/app/src/pages/index.tsx", None));
+}
+
+#[test]
+fn test_nested_route_manifest() {
+  let context = create_context("nested_route_manifest");
+  std::fs::create_dir_all(context.join("config")).unwrap();
+  std::fs::write(
+    context.join("config/routes.json"),
+    json!([{
+      "file": "layout.tsx",
+      "children": [
+        { "file": "index.tsx" },
+        { "file": "./blog/layout.tsx", "children": [{ "file": "blog/[id].tsx" }] },
+      ],
+    }])
+    .to_string(),
+  )
+  .unwrap();
+  let options = routes_options(json!({ "manifest": "./config/routes.json", "root": "app" }));
+  let routes = get_routes_config(&context, &options, CompilationId::new());
+  assert_eq!(routes.manifest, Some(context.join("config/routes.json")));
+  let routes = routes.files.as_deref();
+  assert!(is_entry(
+    context.join("app/blog/[id].tsx").to_str().unwrap(),
+    routes
+  ));
+  assert!(is_entry(
+    context.join("app/./blog/layout.tsx").to_str().unwrap(),
+    routes
+  ));
+  assert!(!is_entry(
+    context.join("app/blog/index.tsx").to_str().unwrap(),
+    routes
+  ));
+}
+
+#[test]
+fn test_route_manifest_cache() {
+  let context = create_context("route_manifest_cache");
+  let manifest = context.join("routes.json");
+  let options = routes_options(json!({ "manifest": "routes.json" }));
+  let write_routes = |file: &str| {
+    std::fs::write(&manifest, json!([{ "file": file }]).to_string()).unwrap();
+  };
+
+  write_routes("home.tsx");
+  let compilation_id = CompilationId::new();
+  let first = get_routes_config(&context, &options, compilation_id)
+    .files
+    .unwrap();
+  assert_eq!(*first, vec![context.join("src/pages/home.tsx")]);
+
+  // The manifest is read once per compilation.
+  write_routes("blog.tsx");
+  let cached = get_routes_config(&context, &options, compilation_id)
+    .files
+    .unwrap();
+  assert!(std::sync::Arc::ptr_eq(&first, &cached));
+
+  // Edits of the same size within the same second are picked up by the next compilation.
+  let files = get_routes_config(&context, &options, CompilationId::new())
+    .files
+    .unwrap();
+  assert_eq!(*files, vec![context.join("src/pages/blog.tsx")]);
+
+  // Unchanged manifests are not parsed again.
+  let unchanged = get_routes_config(&context, &options, CompilationId::new())
+    .files
+    .unwrap();
+  assert!(std::sync::Arc::ptr_eq(&files, &unchanged));
+
+  std::fs::remove_file(&manifest).unwrap();
+  assert!(get_routes_config(&context, &options, CompilationId::new())
+    .files
+    .is_none());
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use loader_compilation::{package_has_side_effects, SideEffectsConfig, SideEffectsOptions};
+use serde_json::{json, Value};
+
+fn has_side_effects(package: &str, module: &str) -> bool {
+  let package_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/fixture/side_effects")
+    .join(package);
+  let package_json =
+    std::fs::read_to_string(package_dir.join("package.json")).expect("failed to read package.json");
+  let package_json: Value = serde_json::from_str(&package_json).expect("invalid package.json");
+  package_has_side_effects(
+    &package_dir,
+    &package_json,
+    &package_dir.join(Path::new(module)),
+  )
+}
+
+#[test]
+fn test_missing_field() {
+  assert!(has_side_effects("no_field", "src/index.js"));
+}
+
+#[test]
+fn test_disabled() {
+  assert!(!has_side_effects("disabled", "src/index.js"));
+}
+
+#[test]
+fn test_patterns() {
+  assert!(has_side_effects("patterns", "src/style.css"));
+  assert!(has_side_effects("patterns", "src/polyfill.js"));
+  assert!(!has_side_effects("patterns", "src/index.js"));
+}
+
+fn module_has_side_effects(options: SideEffectsOptions, module: &str) -> bool {
+  let config = SideEffectsConfig::new(&options).expect("invalid side effects options");
+  let module = Path::new(module);
+  let package_dir = module
+    .ancestors()
+    .find(|dir| {
+      dir
+        .parent()
+        .is_some_and(|parent| parent.ends_with("node_modules"))
+    })
+    .unwrap_or(Path::new("/app"));
+  config.module_has_side_effects(module, Some((package_dir, &json!({ "name": "pkg" }))))
+}
+
+#[test]
+fn test_app_modules_without_field() {
+  let options = || SideEffectsOptions {
+    include: None,
+    package_json: true,
+  };
+  assert!(!module_has_side_effects(options(), "/app/src/server/db.js"));
+  assert!(module_has_side_effects(
+    options(),
+    "/app/node_modules/pkg/index.js"
+  ));
+}
+
+#[test]
+fn test_include_modules() {
+  let options = || SideEffectsOptions {
+    include: Some(vec!["**/polyfill.js".to_string()]),
+    package_json: false,
+  };
+  assert!(module_has_side_effects(options(), "/app/src/polyfill.js"));
+  assert!(!module_has_side_effects(options(), "/app/src/server/db.js"));
+  assert!(!module_has_side_effects(
+    options(),
+    "/app/node_modules/pkg/index.js"
+  ));
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use loader_compilation::resolve_syntax;
+use swc_core::common::{sync::Lrc, FileName, SourceMap};
+use swc_core::ecma::ast::EsVersion;
+use swc_core::ecma::parser::{parse_file_as_module, EsSyntax, Syntax, TsSyntax};
+
+fn syntax(resource: &str, configured: Option<Syntax>) -> Option<Syntax> {
+  let (path, query) = match resource.split_once('?') {
+    Some((path, query)) => (path, Some(query)),
+    None => (resource, None),
+  };
+  resolve_syntax(Path::new(path), query, configured)
+}
+
+fn ts_syntax(syntax: Option<Syntax>) -> TsSyntax {
+  match syntax {
+    Some(Syntax::Typescript(ts)) => ts,
+    _ => panic!("expected typescript syntax, got {syntax:?}"),
+  }
+}
+
+fn es_syntax(syntax: Option<Syntax>) -> EsSyntax {
+  match syntax {
+    Some(Syntax::Es(es)) => es,
+    _ => panic!("expected ecmascript syntax, got {syntax:?}"),
+  }
+}
+
+fn parses(source: &str, syntax: Syntax) -> bool {
+  let cm: Lrc<SourceMap> = Default::default();
+  let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+  let mut errors = vec![];
+  parse_file_as_module(&fm, syntax, EsVersion::latest(), None, &mut errors).is_ok()
+    && errors.is_empty()
+}
+
+#[test]
+fn test_typescript_extensions() {
+  let ts = ts_syntax(syntax("/app/src/index.ts", None));
+  assert!(!ts.tsx && ts.decorators && !ts.disallow_ambiguous_jsx_like);
+
+  for resource in ["/app/src/index.mts", "/app/src/index.cts"] {
+    let ts = ts_syntax(syntax(resource, None));
+    assert!(!ts.tsx && ts.decorators && ts.disallow_ambiguous_jsx_like);
+  }
+
+  let ts = ts_syntax(syntax("/app/src/index.tsx", None));
+  assert!(ts.tsx && ts.decorators);
+}
+
+#[test]
+fn test_extension_overrides_configured_syntax() {
+  // A project configured for JavaScript still compiles its TypeScript modules.
+  let configured = Some(Syntax::Es(Default::default()));
+  assert!(ts_syntax(syntax("/app/src/index.tsx", configured)).tsx);
+
+  let configured = Some(Syntax::Typescript(TsSyntax {
+    tsx: true,
+    ..Default::default()
+  }));
+  assert!(!ts_syntax(syntax("/app/src/index.ts", configured)).tsx);
+}
+
+#[test]
+fn test_jsx_extension() {
+  let es = es_syntax(syntax("/app/src/index.jsx", None));
+  assert!(es.jsx);
+
+  // Other options of the configured syntax are kept.
+  let configured = Some(Syntax::Es(EsSyntax {
+    decorators: true,
+    ..Default::default()
+  }));
+  let es = es_syntax(syntax("/app/src/index.jsx", configured));
+  assert!(es.jsx && es.decorators);
+}
+
+#[test]
+fn test_configured_syntax_fallback() {
+  let configured = Some(Syntax::Es(EsSyntax {
+    jsx: true,
+    ..Default::default()
+  }));
+  assert!(es_syntax(syntax("/app/src/index.js", configured)).jsx);
+  assert!(syntax("/app/src/index.js", None).is_none());
+}
+
+#[test]
+fn test_query_lang() {
+  assert!(ts_syntax(syntax("virtual-module?lang=tsx", None)).tsx);
+  assert!(!ts_syntax(syntax("virtual-module?id=1&lang=ts", None)).tsx);
+  assert!(es_syntax(syntax("virtual-module?lang=jsx", None)).jsx);
+
+  // Modules without `lang` use the configured syntax.
+  let configured = Some(Syntax::Es(Default::default()));
+  assert!(!es_syntax(syntax("virtual-module?id=1", configured)).jsx);
+  assert!(syntax("virtual-module", None).is_none());
+
+  // The extension takes precedence over the query.
+  assert!(!ts_syntax(syntax("/app/src/index.ts?lang=tsx", None)).tsx);
+}
+
+#[test]
+fn test_type_assertions() {
+  let source = "const value = <string>input;\nexport const id = <T>(value: T) => value;";
+  assert!(parses(source, syntax("/app/src/index.ts", None).unwrap()));
+  // `<string>input` is parsed as JSX in `.tsx` files.
+  assert!(!parses(source, syntax("/app/src/index.tsx", None).unwrap()));
+  // `.mts` and `.cts` do not allow type assertions which look like JSX.
+  assert!(!parses(source, syntax("/app/src/index.mts", None).unwrap()));
+}
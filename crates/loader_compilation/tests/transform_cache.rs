@@ -0,0 +1,115 @@
+use std::{
+  collections::BTreeMap,
+  sync::Arc,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use loader_compilation::{TransformCache, TransformCacheOptions};
+use rspack_fs::NativeFileSystem;
+use rspack_paths::Utf8PathBuf;
+use rspack_plugin_javascript::TransformOutput;
+
+const SOURCE: &str = "export const getData = () => import('./server');";
+const OPTIONS: &str = r#"{"transformFeatures":{"keepExport":["default"]}}"#;
+
+// Every test works in its own context so that tests can run in parallel.
+fn create_cache(name: &str, max_age: Option<u64>) -> TransformCache {
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_nanos();
+  let context = std::env::temp_dir().join(format!(
+    "loader_compilation_transform_cache_{name}_{}_{nanos}",
+    std::process::id()
+  ));
+  TransformCache::new(
+    Arc::new(NativeFileSystem::new(false)),
+    &Utf8PathBuf::from(context.to_string_lossy().to_string()),
+    &TransformCacheOptions {
+      directory: None,
+      max_age,
+    },
+  )
+}
+
+fn output(code: &str) -> TransformOutput {
+  TransformOutput {
+    code: code.to_string(),
+    map: None,
+  }
+}
+
+#[tokio::test]
+async fn test_hit() {
+  let cache = create_cache("hit", None);
+  let key = TransformCache::key(&[SOURCE.as_bytes(), OPTIONS.as_bytes()]);
+  let side_effects = BTreeMap::from([("./server".to_string(), false)]);
+  cache
+    .set(
+      key,
+      &output("export default 1;"),
+      None,
+      side_effects.clone(),
+    )
+    .await;
+
+  let cached = cache.get(key).await.expect("cached output");
+  assert_eq!(cached.output.code, "export default 1;");
+  assert_eq!(cached.side_effects, side_effects);
+}
+
+#[tokio::test]
+async fn test_miss_when_source_or_options_change() {
+  let cache = create_cache("miss", None);
+  let key = TransformCache::key(&[SOURCE.as_bytes(), OPTIONS.as_bytes()]);
+  cache
+    .set(key, &output("export default 1;"), None, BTreeMap::new())
+    .await;
+
+  let changed_source = TransformCache::key(&[b"export default 2;", OPTIONS.as_bytes()]);
+  let changed_options = TransformCache::key(&[SOURCE.as_bytes(), b"{}"]);
+  assert_ne!(changed_source, key);
+  assert_ne!(changed_options, key);
+  assert!(cache.get(changed_source).await.is_none());
+  assert!(cache.get(changed_options).await.is_none());
+}
+
+#[test]
+fn test_key_parts_are_delimited() {
+  assert_ne!(
+    TransformCache::key(&[b"ab", b"c"]),
+    TransformCache::key(&[b"a", b"bc"])
+  );
+}
+
+#[tokio::test]
+async fn test_revalidate_side_effects() {
+  let cache = create_cache("side_effects", None);
+  let key = TransformCache::key(&[SOURCE.as_bytes()]);
+  let side_effects = BTreeMap::from([
+    ("./server".to_string(), false),
+    ("./style.css".to_string(), true),
+  ]);
+  cache
+    .set(key, &output("import './style.css';"), None, side_effects)
+    .await;
+
+  let cached = cache.get(key).await.expect("cached output");
+  assert!(cached.side_effects_unchanged(|src| src.ends_with(".css")));
+  // `./server` has side effects now, such as after `sideEffects` is changed in package.json.
+  assert!(!cached.side_effects_unchanged(|_| true));
+}
+
+#[tokio::test]
+async fn test_evict_stale_entries() {
+  let cache = create_cache("evict", Some(0));
+  let key = TransformCache::key(&[SOURCE.as_bytes()]);
+  cache
+    .set(key, &output("export default 1;"), None, BTreeMap::new())
+    .await;
+  assert!(cache.get(key).await.is_some());
+
+  tokio::time::sleep(Duration::from_millis(20)).await;
+  cache.evict_stale_entries().await;
+  assert!(cache.get(key).await.is_none());
+}
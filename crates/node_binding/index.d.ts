@@ -665,6 +665,7 @@ export interface JsStatsAssetsByChunkName {
 }
 export interface JsStatsOptions {
   assets: boolean
+  barrels: boolean
   cachedModules: boolean
   chunks: boolean
   chunkGroupAuxiliary: boolean
@@ -691,9 +692,18 @@ export interface JsStatsGetAssets {
   assets: Array<JsStatsAsset>
   assetsByChunkName: Array<JsStatsAssetsByChunkName>
 }
+export interface JsStatsBarrel {
+  resource: string
+  names: Array<string>
+  targets: Array<string>
+  exportCount: number
+  wildcardDepth: number
+  fallback: boolean
+}
 export interface JsStatsCompilation {
   assets?: Array<JsStatsAsset>
   assetsByChunkName?: Array<JsStatsAssetsByChunkName>
+  barrels?: Array<JsStatsBarrel>
   chunks?: Array<JsStatsChunk>
   entrypoints?: Array<JsStatsChunkGroup>
   errors: Array<JsStatsError>
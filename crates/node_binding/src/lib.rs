@@ -12,7 +12,7 @@ use compiler::{Compiler, CompilerState, CompilerStateGuard};
 use napi::bindgen_prelude::*;
 use rspack_core::{Compilation, PluginExt};
 use rspack_error::Diagnostic;
-use rspack_fs::IntermediateFileSystem;
+use rspack_fs::{IntermediateFileSystem, NativeFileSystem};
 use rspack_fs_node::{NodeFileSystem, ThreadsafeNodeFS};
 use rspack_napi::napi::bindgen_prelude::within_runtime_if_available;
 
@@ -73,15 +73,34 @@ impl Rspack {
     let loader_resolver_factory = (*resolver_factory_reference)
       .get_loader_resolver_factory(compiler_options.resolve_loader.clone());
 
-    let intermediate_filesystem: Option<Arc<dyn IntermediateFileSystem>> =
+    // Same default as the compiler, so that builtin loaders share its intermediate filesystem.
+    let intermediate_filesystem: Arc<dyn IntermediateFileSystem> =
       if let Some(fs) = intermediate_filesystem {
-        Some(Arc::new(NodeFileSystem::new(fs).map_err(|e| {
+        Arc::new(NodeFileSystem::new(fs).map_err(|e| {
           Error::from_reason(format!("Failed to create intermediate filesystem: {e}",))
-        })?))
+        })?)
       } else {
-        None
+        Arc::new(NativeFileSystem::new(false))
       };
 
+    // Builtin loaders owning compiler state must be resolved before `JsLoaderRspackPlugin`.
+    plugins.insert(
+      0,
+      loader_barrel::BarrelLoaderPlugin::new(
+        &compiler_options,
+        Some(intermediate_filesystem.clone()),
+      )
+      .boxed(),
+    );
+    plugins.insert(
+      1,
+      loader_compilation::CompilationLoaderPlugin::new(
+        &compiler_options,
+        intermediate_filesystem.clone(),
+      )
+      .boxed(),
+    );
+
     let rspack = rspack_core::Compiler::new(
       compiler_path,
       compiler_options,
@@ -90,7 +109,7 @@ impl Rspack {
       Some(Arc::new(NodeFileSystem::new(output_filesystem).map_err(
         |e| Error::from_reason(format!("Failed to create writable filesystem: {e}",)),
       )?)),
-      intermediate_filesystem,
+      Some(intermediate_filesystem),
       None,
       Some(resolver_factory),
       Some(loader_resolver_factory),
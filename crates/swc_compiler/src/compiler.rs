@@ -125,6 +125,10 @@ impl SwcCompiler {
     GLOBALS.set(&self.globals, op)
   }
 
+  pub fn cm(&self) -> &Arc<SourceMap> {
+    &self.cm
+  }
+
   pub fn parse<'a, P>(
     &'a self,
     program: Option<Program>,
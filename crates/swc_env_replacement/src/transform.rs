@@ -1,12 +1,171 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
 use swc_core::{
-  common::{SyntaxContext, DUMMY_SP},
+  common::{FileName, SourceMap, Span, SyntaxContext, DUMMY_SP},
   ecma::{
     ast::*,
-    visit::{Fold, FoldWith, fold_pass},
+    parser::{parse_file_as_expr, Syntax},
+    visit::{Fold, FoldWith, VisitMut, VisitMutWith, fold_pass},
   },
 };
+
+pub const DEFAULT_ENV_SOURCES: [&str; 2] = ["@uni/env", "universal-env"];
+
+#[derive(Debug, Clone)]
+pub struct EnvReplacementConfig {
+  // Packages whose imports are replaced with env flags.
+  pub sources: Vec<String>,
+  // User defined flags, take precedence over the built-in flags.
+  pub flags: HashMap<String, Expr>,
+  // Known `import.meta.renderer` and `import.meta.target` of the current build,
+  // env flags are folded to boolean literals when they can be decided at build time.
+  pub renderer: Option<String>,
+  pub target: Option<String>,
+}
+
+impl Default for EnvReplacementConfig {
+  fn default() -> Self {
+    Self {
+      sources: DEFAULT_ENV_SOURCES.iter().map(|s| s.to_string()).collect(),
+      flags: Default::default(),
+      renderer: None,
+      target: None,
+    }
+  }
+}
+
 struct EnvReplacement {
-  sources: Vec<String>,
+  config: EnvReplacementConfig,
+  // Locals bound to the whole env module, such as `import env from '@uni/env'`.
+  env_locals: HashSet<Id>,
+}
+
+struct DropSpan;
+
+impl VisitMut for DropSpan {
+  fn visit_mut_span(&mut self, span: &mut Span) {
+    *span = DUMMY_SP;
+  }
+}
+
+// Replace known `import.meta.*` checks with boolean literals, and simplify the
+// logical expressions they are part of.
+//
+// Only a constant left operand of `&&` and `||` is simplified. `x && false` is kept as is,
+// because `x` may have side effects or a non-boolean value that the expression evaluates to.
+struct EnvConstantFolder<'a> {
+  config: &'a EnvReplacementConfig,
+}
+
+impl EnvConstantFolder<'_> {
+  fn get_meta_value(&self, expr: &Expr) -> Option<&str> {
+    let Expr::Member(MemberExpr {
+      obj: box Expr::MetaProp(MetaPropExpr {
+        kind: MetaPropKind::ImportMeta,
+        ..
+      }),
+      prop: MemberProp::Ident(prop),
+      ..
+    }) = expr
+    else {
+      return None;
+    };
+    match prop.sym.as_ref() {
+      "renderer" => self.config.renderer.as_deref(),
+      "target" => self.config.target.as_deref(),
+      _ => None,
+    }
+  }
+}
+
+fn as_bool(expr: &Expr) -> Option<bool> {
+  match expr {
+    Expr::Lit(Lit::Bool(Bool { value, .. })) => Some(*value),
+    _ => None,
+  }
+}
+
+impl VisitMut for EnvConstantFolder<'_> {
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    expr.visit_mut_children_with(self);
+    let Expr::Bin(BinExpr {
+      op, left, right, ..
+    }) = expr
+    else {
+      return;
+    };
+    let folded = match op {
+      BinaryOp::EqEqEq | BinaryOp::NotEqEq => {
+        let (value, lit) = match (self.get_meta_value(left), &**right) {
+          (Some(value), Expr::Lit(Lit::Str(lit))) => (value, lit),
+          _ => return,
+        };
+        let equals = lit.value == *value;
+        Expr::Lit(Lit::Bool(Bool {
+          span: DUMMY_SP,
+          value: if *op == BinaryOp::EqEqEq { equals } else { !equals },
+        }))
+      }
+      // `true && x` is `x`, `false && x` is `false`.
+      BinaryOp::LogicalAnd => match as_bool(left) {
+        Some(true) => *right.clone(),
+        Some(false) => *left.clone(),
+        None => return,
+      },
+      // `true || x` is `true`, `false || x` is `x`.
+      BinaryOp::LogicalOr => match as_bool(left) {
+        Some(true) => *left.clone(),
+        Some(false) => *right.clone(),
+        None => return,
+      },
+      _ => return,
+    };
+    *expr = folded;
+  }
+}
+
+// Give synthesized nodes the span of the code they replace, so that source maps
+// point to the original env usage. Identifiers keep their own spans.
+struct Respan(Span);
+
+impl VisitMut for Respan {
+  fn visit_mut_span(&mut self, span: &mut Span) {
+    if span.is_dummy() {
+      *span = self.0;
+    }
+  }
+
+  fn visit_mut_ident(&mut self, _: &mut Ident) {}
+
+  fn visit_mut_ident_name(&mut self, _: &mut IdentName) {}
+}
+
+fn respan<N: VisitMutWith<Respan>>(mut node: N, span: Span) -> N {
+  node.visit_mut_with(&mut Respan(span));
+  node
+}
+
+/// Parse the expression of a user defined env flag, such as `/MyApp/.test(navigator.userAgent)`.
+pub fn parse_env_flag(name: &str, source: &str) -> Result<Expr, String> {
+  let cm = SourceMap::default();
+  let fm = cm.new_source_file(
+    Arc::new(FileName::Custom(format!("env flag {name}"))),
+    source.to_string(),
+  );
+  let mut expr = parse_file_as_expr(
+    &fm,
+    Syntax::Es(Default::default()),
+    EsVersion::latest(),
+    None,
+    &mut vec![],
+  )
+  .map_err(|e| format!("failed to parse env flag `{name}`: {:?}", e.kind()))?;
+  // Spans of the expression point to a different source map.
+  expr.visit_mut_with(&mut DropSpan);
+  Ok(*expr)
 }
 
 fn create_check_expr(meta_value: &str, renderer: &str) -> Expr {
@@ -29,6 +188,17 @@ fn create_check_expr(meta_value: &str, renderer: &str) -> Expr {
   })
 }
 
+// import.meta.renderer === 'client' && import.meta.target === '<target>'
+fn create_target_check(target: &str) -> Expr {
+  combine_check_exprs(
+    vec![
+      create_check_expr("renderer", "client"),
+      create_check_expr("target", target),
+    ],
+    BinaryOp::LogicalAnd,
+  )
+}
+
 fn create_typeof_check(expr: Expr, check_value: &str, op: BinaryOp) -> Expr {
   // Create `typeof pha` unary expression
   let typeof_expr = Expr::Unary(UnaryExpr {
@@ -123,36 +293,33 @@ fn build_regex_test_expression() -> Expr {
   test_call
 }
 
-fn get_env_expr(specifier: &Ident) -> Expr {
+fn get_env_expr(specifier: &Ident, config: &EnvReplacementConfig) -> Expr {
+  let mut expr = get_runtime_env_expr(specifier, config);
+  if config.renderer.is_some() || config.target.is_some() {
+    expr.visit_mut_with(&mut EnvConstantFolder { config });
+  }
+  expr
+}
+
+fn get_runtime_env_expr(specifier: &Ident, config: &EnvReplacementConfig) -> Expr {
+  if let Some(expr) = config.flags.get(specifier.sym.as_ref()) {
+    return expr.clone();
+  }
   match specifier.sym.as_ref() {
     "isClient" => create_check_expr("renderer", "client"),
     "isServer" => create_check_expr("renderer", "server"),
-    "isWeb" => combine_check_exprs(
-      vec![
-        create_check_expr("renderer", "client"),
-        create_check_expr("target", "web"),
-      ],
-      BinaryOp::LogicalAnd,
-    ),
+    "isWeb" => create_target_check("web"),
     "isNode" => create_check_expr("renderer", "server"),
-    "isWeex" => combine_check_exprs(
-      vec![
-        create_check_expr("renderer", "client"),
-        create_check_expr("target", "weex"),
-      ],
-      BinaryOp::LogicalAnd,
-    ),
-    "isKraken" => combine_check_exprs(
-      vec![
-        create_check_expr("renderer", "client"),
-        create_check_expr("target", "kraken"),
-      ],
-      BinaryOp::LogicalAnd,
-    ),
+    "isWeex" => create_target_check("weex"),
+    "isKraken" => create_target_check("kraken"),
+    "isMiniApp" => create_target_check("miniapp"),
+    "isWeChatMiniProgram" => create_target_check("wechat-miniprogram"),
+    "isByteDanceMicroApp" => create_target_check("bytedance-microapp"),
+    "isBaiduSmartProgram" => create_target_check("baidu-smartprogram"),
+    "isKuaiShouMiniProgram" => create_target_check("kuaishou-miniprogram"),
     "isPHA" => combine_check_exprs(
       vec![
-        create_check_expr("renderer", "client"),
-        create_check_expr("target", "web"),
+        create_target_check("web"),
         create_typeof_check(
           Expr::Ident(Ident::new("pha".into(), DUMMY_SP, SyntaxContext::empty())),
           "object",
@@ -183,7 +350,7 @@ fn get_env_expr(specifier: &Ident) -> Expr {
       BinaryOp::LogicalAnd,
     ),
     _ => {
-      // Do not support miniapp env.
+      // Do not support other env, such as quick app.
       Expr::Lit(Lit::Bool(Bool {
         span: DUMMY_SP,
         value: false,
@@ -192,10 +359,15 @@ fn get_env_expr(specifier: &Ident) -> Expr {
   }
 }
 
-fn create_env_declare(specifier: &Ident, imported: &Ident) -> Stmt {
-  let expr = get_env_expr(&specifier);
+fn create_env_declare(
+  specifier: &Ident,
+  imported: &Ident,
+  config: &EnvReplacementConfig,
+  span: Span,
+) -> Stmt {
+  let expr = get_env_expr(&specifier, config);
 
-  return Stmt::Decl(Decl::Var(Box::new(VarDecl {
+  return respan(Stmt::Decl(Decl::Var(Box::new(VarDecl {
     span: DUMMY_SP,
     kind: VarDeclKind::Var,
     declare: false,
@@ -209,11 +381,58 @@ fn create_env_declare(specifier: &Ident, imported: &Ident) -> Stmt {
       init: Some(Box::new(expr)),
       definite: false,
     }],
-  })));
+  }))), span);
+}
+
+const BUILTIN_ENV_FLAGS: [&str; 14] = [
+  "isWeb",
+  "isClient",
+  "isNode",
+  "isWeex",
+  "isKraken",
+  "isMiniApp",
+  "isByteDanceMicroApp",
+  "isBaiduSmartProgram",
+  "isKuaiShouMiniProgram",
+  "isWeChatMiniProgram",
+  "isQuickApp",
+  "isPHA",
+  "isWindVane",
+  "isFRM",
+];
+
+// Built-in flags followed by the user defined flags.
+fn get_env_flag_names(config: &EnvReplacementConfig) -> Vec<&str> {
+  let mut custom_flags = config
+    .flags
+    .keys()
+    .map(String::as_str)
+    .filter(|flag| !BUILTIN_ENV_FLAGS.contains(flag))
+    .collect::<Vec<_>>();
+  custom_flags.sort();
+  BUILTIN_ENV_FLAGS.into_iter().chain(custom_flags).collect()
+}
+
+fn create_env_object(config: &EnvReplacementConfig) -> Expr {
+  Expr::Object(ObjectLit {
+    span: DUMMY_SP,
+    props: get_env_flag_names(config)
+      .into_iter()
+      .map(|target| {
+        PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+          key: PropName::Ident(Ident::new(target.into(), DUMMY_SP, SyntaxContext::empty()).into()),
+          value: Box::new(get_env_expr(
+            &Ident::new(target.into(), DUMMY_SP, SyntaxContext::empty()),
+            config,
+          )),
+        })))
+      })
+      .collect(),
+  })
 }
 
-fn create_env_default_export(export_name: Ident) -> Stmt {
-  Stmt::Decl(Decl::Var(Box::new(VarDecl {
+fn create_const_decl(name: Ident, init: Expr) -> Decl {
+  Decl::Var(Box::new(VarDecl {
     ctxt: Default::default(),
     span: DUMMY_SP,
     kind: VarDeclKind::Const,
@@ -221,42 +440,104 @@ fn create_env_default_export(export_name: Ident) -> Stmt {
     decls: vec![VarDeclarator {
       span: DUMMY_SP,
       name: Pat::Ident(BindingIdent {
-        id: export_name.clone(),
+        id: name,
         type_ann: Default::default(),
       }),
-      init: Some(Box::new(Expr::Object(ObjectLit {
-        span: DUMMY_SP,
-        props: vec![
-          "isWeb",
-          "isClient",
-          "isNode",
-          "isWeex",
-          "isKraken",
-          "isMiniApp",
-          "isByteDanceMicroApp",
-          "isBaiduSmartProgram",
-          "isKuaiShouMiniProgram",
-          "isWeChatMiniProgram",
-          "isQuickApp",
-          "isPHA",
-          "isWindVane",
-          "isFRM",
-        ]
-        .into_iter()
-        .map(|target| {
-          PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
-            key: PropName::Ident(Ident::new(target.into(), DUMMY_SP, SyntaxContext::empty()).into()),
-            value: Box::new(get_env_expr(&Ident::new(target.into(), DUMMY_SP, SyntaxContext::empty()).into())),
-          })))
-        })
-        .collect(),
-      }))),
+      init: Some(Box::new(init)),
       definite: false,
     }],
-  })))
+  }))
+}
+
+fn create_env_default_export(export_name: Ident, config: &EnvReplacementConfig, span: Span) -> Stmt {
+  respan(Stmt::Decl(create_const_decl(export_name, create_env_object(config))), span)
+}
+
+// export const isWeb = ...;
+fn create_env_export(
+  export_name: &str,
+  flag: &str,
+  config: &EnvReplacementConfig,
+  span: Span,
+) -> ModuleItem {
+  let init = if flag == "default" {
+    create_env_object(config)
+  } else {
+    get_env_expr(
+      &Ident::new(flag.into(), DUMMY_SP, SyntaxContext::empty()),
+      config,
+    )
+  };
+  respan(
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+      span: DUMMY_SP,
+      decl: create_const_decl(
+        Ident::new(export_name.into(), DUMMY_SP, SyntaxContext::empty()),
+        init,
+      ),
+    })),
+    span,
+  )
 }
 
-fn get_env_stmt(sources: &Vec<String>, decls: Vec<VarDeclarator>) -> Vec<Stmt> {
+// Module namespace of `import('@uni/env')`, which has the flags and the default export.
+fn create_env_namespace(config: &EnvReplacementConfig) -> Expr {
+  let mut namespace = create_env_object(config);
+  if let Expr::Object(object) = &mut namespace {
+    object
+      .props
+      .push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+        key: PropName::Ident(IdentName::new("default".into(), DUMMY_SP)),
+        value: Box::new(create_env_object(config)),
+      }))));
+  }
+  namespace
+}
+
+fn module_export_name(name: &ModuleExportName) -> &str {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.as_ref(),
+    ModuleExportName::Str(s) => s.value.as_ref(),
+  }
+}
+
+fn get_require_source(expr: &Expr) -> Option<&str> {
+  let Expr::Call(CallExpr {
+    args,
+    callee: Callee::Expr(callee),
+    ..
+  }) = expr
+  else {
+    return None;
+  };
+  match (&**callee, args.as_slice()) {
+    (Expr::Ident(ident), [ExprOrSpread { spread: None, expr }]) if ident.sym == "require" => {
+      match &**expr {
+        Expr::Lit(Lit::Str(Str { value, .. })) => Some(value.as_ref()),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+fn get_member_prop_name(prop: &MemberProp) -> Option<&str> {
+  match prop {
+    MemberProp::Ident(ident) => Some(ident.sym.as_ref()),
+    MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+      Expr::Lit(Lit::Str(s)) => Some(s.value.as_ref()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn get_env_stmt(
+  config: &EnvReplacementConfig,
+  env_locals: &mut HashSet<Id>,
+  decls: Vec<VarDeclarator>,
+) -> Vec<Stmt> {
+  let sources = &config.sources;
   let mut stmts = vec![];
   for decl in decls {
     if let Some(init) = decl.init {
@@ -276,29 +557,35 @@ fn get_env_stmt(sources: &Vec<String>, decls: Vec<VarDeclarator>) -> Vec<Stmt> {
             if sources.iter().any(|s| value == s) {
               match &decl.name {
                 Pat::Ident(BindingIdent { id, .. }) => {
-                  stmts.push(create_env_default_export(id.clone()));
+                  env_locals.insert(id.to_id());
+                  stmts.push(create_env_default_export(id.clone(), config, decl.span));
                 }
                 Pat::Object(ObjectPat { props, .. }) => {
                   props.iter().for_each(|prop| match prop {
                     ObjectPatProp::Assign(AssignPatProp { key, value, .. }) => {
                       if value.is_some() {
                         if let box Expr::Ident(ident) = &value.as_ref().unwrap() {
-                          stmts.push(create_env_declare(key, &ident));
+                          stmts.push(create_env_declare(key, &ident, config, decl.span));
                         }
                       } else {
-                        stmts.push(create_env_declare(key, key));
+                        stmts.push(create_env_declare(key, key, config, decl.span));
                       }
                     }
                     ObjectPatProp::KeyValue(KeyValuePatProp { key, value, .. }) => {
                       if let box Pat::Ident(BindingIdent { id, .. }) = &value {
                         if let PropName::Ident(i) = key {
-                          stmts.push(create_env_declare(&Ident::from(i.as_ref()), &id));
+                          stmts.push(create_env_declare(
+                            &Ident::from(i.as_ref()),
+                            &id,
+                            config,
+                            decl.span,
+                          ));
                         }
                       }
                     }
                     ObjectPatProp::Rest(RestPat { arg, .. }) => {
                       if let box Pat::Ident(BindingIdent { id, .. }) = arg {
-                        stmts.push(create_env_default_export(id.clone()));
+                        stmts.push(create_env_default_export(id.clone(), config, decl.span));
                       }
                     }
                   });
@@ -317,13 +604,70 @@ fn get_env_stmt(sources: &Vec<String>, decls: Vec<VarDeclarator>) -> Vec<Stmt> {
 
 impl Fold for EnvReplacement {
   fn fold_module_items(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+    // Imports are hoisted, collect the env module locals before folding any usage.
+    for item in items.iter() {
+      if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+        if self.is_env_source(&import_decl.src.value) {
+          for specifier in &import_decl.specifiers {
+            match specifier {
+              ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+              | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
+                self.env_locals.insert(local.to_id());
+              }
+              ImportSpecifier::Named(_) => {}
+            }
+          }
+        }
+      }
+    }
     let mut new_module_items: Vec<ModuleItem> = vec![];
     for item in items.iter() {
       match &item {
+        // export { isWeb } from '@uni/env';
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+          span,
+          src: Some(src),
+          specifiers,
+          ..
+        })) if self.is_env_source(&src.value) => {
+          for specifier in specifiers {
+            match specifier {
+              ExportSpecifier::Named(ExportNamedSpecifier { orig, exported, .. }) => {
+                let flag = module_export_name(orig);
+                let export_name = exported.as_ref().map(module_export_name).unwrap_or(flag);
+                new_module_items.push(create_env_export(export_name, flag, &self.config, *span));
+              }
+              ExportSpecifier::Namespace(ExportNamespaceSpecifier { name, .. }) => {
+                new_module_items.push(create_env_export(
+                  module_export_name(name),
+                  "default",
+                  &self.config,
+                  *span,
+                ));
+              }
+              ExportSpecifier::Default(ExportDefaultSpecifier { exported }) => {
+                new_module_items.push(create_env_export(
+                  &exported.sym,
+                  "default",
+                  &self.config,
+                  *span,
+                ));
+              }
+            }
+          }
+        }
+        // export * from '@uni/env';
+        ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll { span, src, .. }))
+          if self.is_env_source(&src.value) =>
+        {
+          for flag in get_env_flag_names(&self.config) {
+            new_module_items.push(create_env_export(flag, flag, &self.config, *span));
+          }
+        }
         // Import declaration.
         ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
           let src = &import_decl.src.value;
-          if self.sources.iter().any(|s| src == s) {
+          if self.is_env_source(src) {
             // Collect all specifiers.
             import_decl
               .specifiers
@@ -342,16 +686,26 @@ impl Fold for EnvReplacement {
                   new_module_items.push(ModuleItem::Stmt(create_env_declare(
                     s,
                     &named_specifier.local,
+                    &self.config,
+                    named_specifier.span,
                   )));
                 }
                 ImportSpecifier::Default(default_specifier) => {
                   new_module_items.push(ModuleItem::Stmt(create_env_default_export(
                     default_specifier.local.clone(),
+                    &self.config,
+                    default_specifier.span,
                   )));
                 }
                 ImportSpecifier::Namespace(namespace_specifier) => {
-                  new_module_items.push(ModuleItem::Stmt(create_env_default_export(
+                  // `import * as env` also exposes the default export as `env.default`.
+                  let decl = create_const_decl(
                     namespace_specifier.local.clone(),
+                    create_env_namespace(&self.config),
+                  );
+                  new_module_items.push(ModuleItem::Stmt(respan(
+                    Stmt::Decl(decl),
+                    namespace_specifier.span,
                   )));
                 }
               });
@@ -360,7 +714,7 @@ impl Fold for EnvReplacement {
           }
         }
         ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
-          let stmt = get_env_stmt(&self.sources, var_decl.decls.clone());
+          let stmt = get_env_stmt(&self.config, &mut self.env_locals, var_decl.decls.clone());
           if stmt.len() > 0 {
             let module_stmts = stmt
               .into_iter()
@@ -368,7 +722,7 @@ impl Fold for EnvReplacement {
               .collect::<Vec<ModuleItem>>();
             new_module_items.extend_from_slice(&module_stmts);
           } else {
-            new_module_items.push(item.clone());
+            new_module_items.push(item.clone().fold_children_with(self));
           }
         }
         _ => {
@@ -379,6 +733,60 @@ impl Fold for EnvReplacement {
     new_module_items
   }
 
+  fn fold_expr(&mut self, expr: Expr) -> Expr {
+    let expr = expr.fold_children_with(self);
+    match &expr {
+      // require('@uni/env').isWeb or env.isWeb
+      Expr::Member(MemberExpr { span, obj, prop }) => {
+        let is_env_module = match &**obj {
+          Expr::Ident(ident) => self.env_locals.contains(&ident.to_id()),
+          obj => get_require_source(obj).is_some_and(|src| self.is_env_source(src)),
+        };
+        if is_env_module {
+          // `env.default` is the env object itself rather than a flag.
+          if let Some(flag) = get_member_prop_name(prop).filter(|flag| *flag != "default") {
+            let expr = get_env_expr(
+              &Ident::new(flag.into(), DUMMY_SP, SyntaxContext::empty()),
+              &self.config,
+            );
+            return respan(expr, *span);
+          }
+        }
+        expr
+      }
+      // await import('@uni/env')
+      Expr::Call(CallExpr {
+        span,
+        callee: Callee::Import(_),
+        args,
+        ..
+      }) if args.len() == 1
+        && matches!(&*args[0].expr, Expr::Lit(Lit::Str(Str { value, .. })) if self.is_env_source(value)) =>
+      {
+        let namespace = Expr::Call(CallExpr {
+          span: DUMMY_SP,
+          ctxt: Default::default(),
+          callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(Expr::Ident(Ident::new(
+              "Promise".into(),
+              DUMMY_SP,
+              SyntaxContext::empty(),
+            ))),
+            prop: MemberProp::Ident(IdentName::new("resolve".into(), DUMMY_SP)),
+          }))),
+          args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::new(create_env_namespace(&self.config)),
+          }],
+          type_args: None,
+        });
+        respan(namespace, *span)
+      }
+      _ => expr,
+    }
+  }
+
   fn fold_block_stmt(&mut self, block: BlockStmt) -> BlockStmt {
     let mut new_stmts: Vec<Stmt> = vec![];
     block
@@ -387,11 +795,11 @@ impl Fold for EnvReplacement {
       .into_iter()
       .for_each(|stmt| match &stmt {
         Stmt::Decl(Decl::Var(var_decl)) => {
-          let env_stmts = get_env_stmt(&self.sources, var_decl.decls.clone());
+          let env_stmts = get_env_stmt(&self.config, &mut self.env_locals, var_decl.decls.clone());
           if env_stmts.len() > 0 {
             new_stmts.extend_from_slice(&env_stmts);
           } else {
-            new_stmts.push(stmt);
+            new_stmts.push(stmt.fold_children_with(self));
           }
         }
         _ => {
@@ -405,6 +813,15 @@ impl Fold for EnvReplacement {
   }
 }
 
-pub fn env_replacement(sources: Vec<String>) -> impl Pass {
-  fold_pass(EnvReplacement { sources })
+impl EnvReplacement {
+  fn is_env_source(&self, src: &str) -> bool {
+    self.config.sources.iter().any(|s| s == src)
+  }
+}
+
+pub fn env_replacement(config: EnvReplacementConfig) -> impl Pass {
+  fold_pass(EnvReplacement {
+    config,
+    env_locals: Default::default(),
+  })
 }
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_env_replacement::{env_replacement, parse_env_flag, EnvReplacementConfig};
+
+// Options of a fixture, read from `config.json` next to the input.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct FixtureConfig {
+  sources: Option<Vec<String>>,
+  flags: HashMap<String, String>,
+  renderer: Option<String>,
+  target: Option<String>,
+}
+
+impl FixtureConfig {
+  fn into_env_config(self) -> EnvReplacementConfig {
+    let mut config = EnvReplacementConfig {
+      renderer: self.renderer,
+      target: self.target,
+      ..Default::default()
+    };
+    if let Some(sources) = self.sources {
+      config.sources = sources;
+    }
+    for (name, expr) in self.flags {
+      let expr = parse_env_flag(&name, &expr).unwrap();
+      config.flags.insert(name, expr);
+    }
+    config
+  }
+}
+
+#[testing::fixture("tests/fixture/**/input.js")]
+fn fixture(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  let config = match fs::read_to_string(input.with_file_name("config.json")) {
+    Ok(json) => serde_json::from_str::<FixtureConfig>(&json).unwrap(),
+    Err(_) => FixtureConfig::default(),
+  };
+  let config = config.into_env_config();
+  test_fixture(
+    Default::default(),
+    &|_t| env_replacement(config.clone()),
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[test]
+fn test_parse_env_flag_error() {
+  let err = parse_env_flag("isBroken", "navigator.userAgent.includes(").unwrap_err();
+  assert!(err.contains("isBroken"), "{err}");
+}
@@ -0,0 +1,2 @@
+import { isMyApp, isWeb } from '@uni/env';
+console.log(isMyApp, isWeb);
@@ -0,0 +1,3 @@
+var isMyApp = /MyApp/.test(navigator.userAgent);
+var isWeb = window.__IS_WEB__;
+console.log(isMyApp, isWeb);
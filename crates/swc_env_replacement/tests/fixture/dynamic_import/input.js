@@ -0,0 +1,4 @@
+async function load() {
+  const env = await import('@uni/env');
+  return env.isNode;
+}
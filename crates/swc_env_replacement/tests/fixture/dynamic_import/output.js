@@ -0,0 +1,35 @@
+async function load() {
+  const env = await Promise.resolve({
+    isWeb: false,
+    isClient: false,
+    isNode: true,
+    isWeex: false,
+    isKraken: false,
+    isMiniApp: false,
+    isByteDanceMicroApp: false,
+    isBaiduSmartProgram: false,
+    isKuaiShouMiniProgram: false,
+    isWeChatMiniProgram: false,
+    isQuickApp: false,
+    isPHA: false,
+    isWindVane: false,
+    isFRM: false,
+    default: {
+      isWeb: false,
+      isClient: false,
+      isNode: true,
+      isWeex: false,
+      isKraken: false,
+      isMiniApp: false,
+      isByteDanceMicroApp: false,
+      isBaiduSmartProgram: false,
+      isKuaiShouMiniProgram: false,
+      isWeChatMiniProgram: false,
+      isQuickApp: false,
+      isPHA: false,
+      isWindVane: false,
+      isFRM: false
+    }
+  });
+  return env.isNode;
+}
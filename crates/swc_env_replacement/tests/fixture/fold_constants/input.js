@@ -0,0 +1,2 @@
+import { isWeb, isNode, isWeex, isPHA } from '@uni/env';
+console.log(isWeb, isNode, isWeex, isPHA);
@@ -0,0 +1,5 @@
+var isWeb = true;
+var isNode = false;
+var isWeex = false;
+var isPHA = typeof pha === "object";
+console.log(isWeb, isNode, isWeex, isPHA);
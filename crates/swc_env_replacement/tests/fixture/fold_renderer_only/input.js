@@ -0,0 +1,2 @@
+import { isWeb, isClient } from '@uni/env';
+console.log(isWeb, isClient);
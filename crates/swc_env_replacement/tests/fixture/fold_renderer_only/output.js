@@ -0,0 +1,3 @@
+var isWeb = import.meta.target === "web";
+var isClient = true;
+console.log(isWeb, isClient);
@@ -0,0 +1,2 @@
+import { isMyApp, isOther } from '@uni/env';
+console.log(isMyApp, isOther);
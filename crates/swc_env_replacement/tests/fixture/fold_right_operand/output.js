@@ -0,0 +1,3 @@
+var isMyApp = navigator.isMyApp && false;
+var isOther = navigator.isOther;
+console.log(isMyApp, isOther);
@@ -0,0 +1,3 @@
+import env from '@uni/env';
+const web = require('@uni/env').isWeb;
+console.log(web, env.isNode, env['isWeex']);
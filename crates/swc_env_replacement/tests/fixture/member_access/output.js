@@ -0,0 +1,18 @@
+const env = {
+  isWeb: false,
+  isClient: false,
+  isNode: true,
+  isWeex: false,
+  isKraken: false,
+  isMiniApp: false,
+  isByteDanceMicroApp: false,
+  isBaiduSmartProgram: false,
+  isKuaiShouMiniProgram: false,
+  isWeChatMiniProgram: false,
+  isQuickApp: false,
+  isPHA: false,
+  isWindVane: false,
+  isFRM: false
+};
+const web = false;
+console.log(web, true, false);
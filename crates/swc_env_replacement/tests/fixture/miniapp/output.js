@@ -0,0 +1,4 @@
+var isMiniApp = import.meta.renderer === "client" && import.meta.target === "miniapp";
+var isWeChatMiniProgram = import.meta.renderer === "client" && import.meta.target === "wechat-miniprogram";
+var isByteDanceMicroApp = import.meta.renderer === "client" && import.meta.target === "bytedance-microapp";
+console.log(isMiniApp, isWeChatMiniProgram, isByteDanceMicroApp);
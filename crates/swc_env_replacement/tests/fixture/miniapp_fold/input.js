@@ -0,0 +1,2 @@
+import { isMiniApp, isWeChatMiniProgram, isByteDanceMicroApp } from '@uni/env';
+console.log(isMiniApp, isWeChatMiniProgram, isByteDanceMicroApp);
@@ -0,0 +1,4 @@
+var isMiniApp = false;
+var isWeChatMiniProgram = true;
+var isByteDanceMicroApp = false;
+console.log(isMiniApp, isWeChatMiniProgram, isByteDanceMicroApp);
@@ -0,0 +1,2 @@
+import * as env from '@uni/env';
+console.log(env.isNode, env.default.isWeb);
@@ -0,0 +1,33 @@
+const env = {
+  isWeb: false,
+  isClient: false,
+  isNode: true,
+  isWeex: false,
+  isKraken: false,
+  isMiniApp: false,
+  isByteDanceMicroApp: false,
+  isBaiduSmartProgram: false,
+  isKuaiShouMiniProgram: false,
+  isWeChatMiniProgram: false,
+  isQuickApp: false,
+  isPHA: false,
+  isWindVane: false,
+  isFRM: false,
+  default: {
+    isWeb: false,
+    isClient: false,
+    isNode: true,
+    isWeex: false,
+    isKraken: false,
+    isMiniApp: false,
+    isByteDanceMicroApp: false,
+    isBaiduSmartProgram: false,
+    isKuaiShouMiniProgram: false,
+    isWeChatMiniProgram: false,
+    isQuickApp: false,
+    isPHA: false,
+    isWindVane: false,
+    isFRM: false
+  }
+};
+console.log(true, env.default.isWeb);
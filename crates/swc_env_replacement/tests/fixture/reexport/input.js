@@ -0,0 +1 @@
+export { isWeb, isNode as node } from '@uni/env';
@@ -0,0 +1,2 @@
+export const isWeb = false;
+export const node = true;
@@ -0,0 +1,3 @@
+import { isWeb } from '@internal/env';
+import { isNode } from '@uni/env';
+console.log(isWeb, isNode);
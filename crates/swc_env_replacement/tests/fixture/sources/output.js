@@ -0,0 +1,3 @@
+var isWeb = import.meta.renderer === "client" && import.meta.target === "web";
+import { isNode } from '@uni/env';
+console.log(isWeb, isNode);
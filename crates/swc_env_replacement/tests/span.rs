@@ -0,0 +1,63 @@
+use swc_core::common::{sync::Lrc, FileName, SourceMap, Span, Spanned};
+use swc_core::ecma::ast::*;
+use swc_core::ecma::parser::{parse_file_as_module, Syntax};
+use swc_env_replacement::{env_replacement, EnvReplacementConfig};
+
+fn transform(source: &str) -> (Module, Module) {
+  let cm: Lrc<SourceMap> = Default::default();
+  let fm = cm.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+  let module = parse_file_as_module(
+    &fm,
+    Syntax::Es(Default::default()),
+    EsVersion::latest(),
+    None,
+    &mut vec![],
+  )
+  .expect("failed to parse source");
+  let mut program = Program::Module(module.clone());
+  env_replacement(EnvReplacementConfig::default()).process(&mut program);
+  (module, program.expect_module())
+}
+
+fn var_decl(item: &ModuleItem) -> &VarDecl {
+  match item {
+    ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var,
+    _ => panic!("expected a variable declaration, got {item:?}"),
+  }
+}
+
+fn assert_decl_span(decl: &VarDecl, span: Span) {
+  assert_eq!(decl.span, span);
+  assert_eq!(decl.decls[0].span, span);
+  assert_eq!(decl.decls[0].init.as_ref().unwrap().span(), span);
+}
+
+#[test]
+fn test_import_specifier_span() {
+  let (input, output) = transform("import { isWeb } from '@uni/env';\n");
+  let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = &input.body[0] else {
+    panic!("expected an import declaration");
+  };
+  assert_decl_span(var_decl(&output.body[0]), import.specifiers[0].span());
+}
+
+#[test]
+fn test_require_declarator_span() {
+  let (input, output) = transform("const { isNode } = require('@uni/env');\n");
+  let declarator_span = var_decl(&input.body[0]).decls[0].span;
+  assert_decl_span(var_decl(&output.body[0]), declarator_span);
+}
+
+#[test]
+fn test_member_access_span() {
+  let (input, output) = transform("console.log(require('@uni/env').isWeb);\n");
+  let arg_span = |module: &Module| match &module.body[0] {
+    ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr: box_expr, .. })) => match &**box_expr {
+      Expr::Call(call) => call.args[0].expr.span(),
+      _ => panic!("expected a call expression"),
+    },
+    _ => panic!("expected an expression statement"),
+  };
+  assert!(!arg_span(&output).is_dummy());
+  assert_eq!(arg_span(&output), arg_span(&input));
+}
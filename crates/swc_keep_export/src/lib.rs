@@ -1,2 +1,8 @@
+mod pattern;
+mod report;
+mod side_effects;
 mod transform;
+pub use pattern::*;
+pub use report::*;
+pub use side_effects::*;
 pub use transform::*;
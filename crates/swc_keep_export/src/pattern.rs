@@ -0,0 +1,53 @@
+use regex::{Regex, RegexBuilder};
+
+/// Pattern of export names.
+///
+/// - `/source/flags` is a regular expression, such as `/^(server|static)DataLoader$/`.
+/// - Names containing `*`, `?` or `[` are globs, such as `getConfig*`.
+/// - Other names are matched exactly.
+#[derive(Debug, Clone)]
+pub enum ExportPattern {
+  Name(String),
+  Glob(glob::Pattern),
+  Regex(Regex),
+}
+
+impl ExportPattern {
+  pub fn parse(pattern: &str) -> Result<Self, String> {
+    if let Some((source, flags)) = pattern
+      .strip_prefix('/')
+      .and_then(|rest| rest.rsplit_once('/'))
+    {
+      if let Some(flag) = flags.chars().find(|flag| !matches!(flag, 'i' | 'm' | 's')) {
+        return Err(format!("invalid flag `{flag}` in export name regex `{pattern}`"));
+      }
+      return RegexBuilder::new(source)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .build()
+        .map(ExportPattern::Regex)
+        .map_err(|e| format!("invalid export name regex `{pattern}`: {e}"));
+    }
+    if pattern.contains(['*', '?', '[']) {
+      return glob::Pattern::new(pattern)
+        .map(ExportPattern::Glob)
+        .map_err(|e| format!("invalid export name glob `{pattern}`: {e}"));
+    }
+    Ok(ExportPattern::Name(pattern.to_string()))
+  }
+
+  pub fn matches(&self, name: &str) -> bool {
+    match self {
+      ExportPattern::Name(n) => n == name,
+      ExportPattern::Glob(glob) => glob.matches(name),
+      ExportPattern::Regex(regex) => regex.is_match(name),
+    }
+  }
+}
+
+impl From<&str> for ExportPattern {
+  fn from(name: &str) -> Self {
+    ExportPattern::Name(name.to_string())
+  }
+}
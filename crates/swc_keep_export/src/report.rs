@@ -0,0 +1,90 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+/// What `keep_export` or `remove_export` pruned from a module.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExportSummary {
+  /// Export names kept in the module.
+  pub kept: BTreeSet<String>,
+  /// Export names removed from the module.
+  pub removed: BTreeSet<String>,
+  /// Sources of the `export *` statements, mapped to whether they were kept.
+  pub export_all: BTreeMap<String, bool>,
+  /// Local names of the import specifiers dropped, keyed by the import source.
+  pub dropped_imports: BTreeMap<String, BTreeSet<String>>,
+  /// Sources of the imports kept for their side effects.
+  pub side_effect_imports: BTreeSet<String>,
+  /// Names of the top-level declarations removed because only removed exports used them.
+  pub removed_declarations: BTreeSet<String>,
+}
+
+impl ExportSummary {
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  pub fn merge(&mut self, other: ExportSummary) {
+    self.kept.extend(other.kept);
+    self.removed.extend(other.removed);
+    self.export_all.extend(other.export_all);
+    for (src, locals) in other.dropped_imports {
+      self.dropped_imports.entry(src).or_default().extend(locals);
+    }
+    self.side_effect_imports.extend(other.side_effect_imports);
+    self.removed_declarations.extend(other.removed_declarations);
+  }
+}
+
+/// Handle shared with the transform to read its [ExportSummary] after it runs.
+///
+/// The transform runs repeatedly until nothing changes, so everything is recorded to sets.
+#[derive(Debug, Default, Clone)]
+pub struct ExportReport(Arc<Mutex<ExportSummary>>);
+
+impl ExportReport {
+  fn update(&self, f: impl FnOnce(&mut ExportSummary)) {
+    f(&mut self.0.lock().expect("failed to lock export report"));
+  }
+
+  pub fn record_export(&self, name: &str, kept: bool) {
+    self.update(|summary| {
+      if kept {
+        summary.kept.insert(name.to_string());
+      } else {
+        summary.removed.insert(name.to_string());
+      }
+    });
+  }
+
+  pub fn record_export_all(&self, src: &str, kept: bool) {
+    self.update(|summary| {
+      summary.export_all.insert(src.to_string(), kept);
+    });
+  }
+
+  pub fn record_dropped_import(&self, src: &str, local: &str) {
+    self.update(|summary| {
+      summary
+        .dropped_imports
+        .entry(src.to_string())
+        .or_default()
+        .insert(local.to_string());
+    });
+  }
+
+  pub fn record_side_effect_import(&self, src: &str) {
+    self.update(|summary| {
+      summary.side_effect_imports.insert(src.to_string());
+    });
+  }
+
+  pub fn record_removed_declaration(&self, name: &str) {
+    self.update(|summary| {
+      summary.removed_declarations.insert(name.to_string());
+    });
+  }
+
+  pub fn summary(&self) -> ExportSummary {
+    self.0.lock().expect("failed to lock export report").clone()
+  }
+}
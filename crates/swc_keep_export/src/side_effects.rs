@@ -0,0 +1,111 @@
+use std::fmt;
+use std::sync::Arc;
+
+use swc_core::ecma::ast::*;
+
+/// Decides whether the module of an import source has side effects. Imports of such modules
+/// are kept as bare imports, such as `import './polyfill'`, after their specifiers are pruned.
+///
+/// The default never reports side effects, so imports without specifiers left are removed.
+#[derive(Clone, Default)]
+pub struct SideEffects(Option<Arc<dyn Fn(&str) -> bool + Send + Sync>>);
+
+impl SideEffects {
+  pub fn new(has_side_effects: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+    Self(Some(Arc::new(has_side_effects)))
+  }
+
+  pub fn has_side_effects(&self, src: &str) -> bool {
+    self.0.as_ref().is_some_and(|f| f(src))
+  }
+}
+
+impl fmt::Debug for SideEffects {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("SideEffects")
+      .field(&self.0.as_ref().map(|_| ".."))
+      .finish()
+  }
+}
+
+/// Whether evaluating `expr` may have side effects, such as `init()` or reading a property
+/// which may be a getter. Anything not known to be free of side effects is assumed to have some.
+pub fn may_have_side_effects(expr: &Expr) -> bool {
+  match expr {
+    Expr::Lit(_) | Expr::Ident(_) | Expr::This(_) | Expr::Arrow(_) | Expr::Fn(_) => false,
+    Expr::Tpl(tpl) => tpl.exprs.iter().any(|expr| may_have_side_effects(expr)),
+    Expr::Array(array) => array
+      .elems
+      .iter()
+      .flatten()
+      .any(|elem| elem.spread.is_some() || may_have_side_effects(&elem.expr)),
+    Expr::Object(object) => object.props.iter().any(|prop| match prop {
+      PropOrSpread::Spread(_) => true,
+      PropOrSpread::Prop(prop) => match &**prop {
+        Prop::Shorthand(_) => false,
+        Prop::KeyValue(prop) => {
+          prop_name_may_have_side_effects(&prop.key) || may_have_side_effects(&prop.value)
+        }
+        Prop::Method(prop) => prop_name_may_have_side_effects(&prop.key),
+        Prop::Getter(prop) => prop_name_may_have_side_effects(&prop.key),
+        Prop::Setter(prop) => prop_name_may_have_side_effects(&prop.key),
+        Prop::Assign(_) => true,
+      },
+    }),
+    Expr::Class(class) => class_may_have_side_effects(&class.class),
+    Expr::Paren(paren) => may_have_side_effects(&paren.expr),
+    Expr::Unary(unary) => unary.op == UnaryOp::Delete || may_have_side_effects(&unary.arg),
+    Expr::Bin(bin) => may_have_side_effects(&bin.left) || may_have_side_effects(&bin.right),
+    Expr::Cond(cond) => {
+      may_have_side_effects(&cond.test)
+        || may_have_side_effects(&cond.cons)
+        || may_have_side_effects(&cond.alt)
+    }
+    Expr::Seq(seq) => seq.exprs.iter().any(|expr| may_have_side_effects(expr)),
+    Expr::TsAs(TsAsExpr { expr, .. })
+    | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. })
+    | Expr::TsConstAssertion(TsConstAssertion { expr, .. })
+    | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+    | Expr::TsTypeAssertion(TsTypeAssertion { expr, .. })
+    | Expr::TsInstantiation(TsInstantiation { expr, .. }) => may_have_side_effects(expr),
+    _ => true,
+  }
+}
+
+/// Whether a declaration runs code with side effects when the module is evaluated,
+/// such as `const store = createStore()`.
+pub fn decl_may_have_side_effects(decl: &Decl) -> bool {
+  match decl {
+    Decl::Var(var) => var
+      .decls
+      .iter()
+      .any(|d| d.init.as_deref().is_some_and(may_have_side_effects)),
+    Decl::Class(class) => class_may_have_side_effects(&class.class),
+    Decl::Using(_) => true,
+    _ => false,
+  }
+}
+
+fn prop_name_may_have_side_effects(name: &PropName) -> bool {
+  match name {
+    PropName::Computed(computed) => may_have_side_effects(&computed.expr),
+    _ => false,
+  }
+}
+
+fn class_may_have_side_effects(class: &Class) -> bool {
+  !class.decorators.is_empty()
+    || class
+      .super_class
+      .as_deref()
+      .is_some_and(may_have_side_effects)
+    || class.body.iter().any(|member| match member {
+      ClassMember::StaticBlock(_) => true,
+      ClassMember::Method(method) => prop_name_may_have_side_effects(&method.key),
+      ClassMember::ClassProp(prop) => {
+        prop_name_may_have_side_effects(&prop.key)
+          || (prop.is_static && prop.value.as_deref().is_some_and(may_have_side_effects))
+      }
+      _ => false,
+    })
+}
@@ -3,6 +3,7 @@
 use std::mem::take;
 
 use fxhash::FxHashSet;
+use crate::{may_have_side_effects, ExportPattern, ExportReport, SideEffects};
 use swc_core::{
   common::{
     pass::{Repeat, Repeated},
@@ -28,17 +29,40 @@ struct State {
   /// functions as the data function itself is already removed.
   refs_used: FxHashSet<Id>,
 
+  /// Names exported by declarations and named exports of the module, excluding `export *`.
+  local_exports: FxHashSet<String>,
+
   should_run_again: bool,
-  keep_exports: Vec<String>,
+  keep_exports: Vec<ExportPattern>,
+  report: ExportReport,
+  side_effects: SideEffects,
+  /// Keep the statements run for their side effects, such as `import './style.css'`,
+  /// top-level calls and declarations initialized with calls.
+  keep_module_side_effects: bool,
 }
 
 impl State {
+  /// `export *` never re-exports `default`, so it is dropped only if every other kept export
+  /// is exported by the module itself.
+  fn should_keep_export_all(&self) -> bool {
+    self.keep_exports.iter().any(|pattern| match pattern {
+      ExportPattern::Name(name) => name != "default" && !self.local_exports.contains(name),
+      _ => true,
+    })
+  }
+
+  fn should_keep_export(&mut self, name: &str) -> bool {
+    let keep = self.keep_exports.iter().any(|pattern| pattern.matches(name));
+    self.report.record_export(name, keep);
+    keep
+  }
+
   fn should_keep_identifier(&mut self, i: &Ident) -> bool {
-    self.keep_exports.contains(&String::from(&*i.sym))
+    self.should_keep_export(&i.sym)
   }
 
   fn should_keep_default(&mut self) -> bool {
-    self.keep_exports.contains(&String::from("default"))
+    self.should_keep_export("default")
   }
 }
 
@@ -63,6 +87,7 @@ impl KeepExport {
       state: &mut self.state,
       in_lhs_of_var: false,
       in_kept_fn: false,
+      in_ts_module: false,
     };
 
     let n = n.fold_with(&mut v);
@@ -98,6 +123,7 @@ impl Fold for KeepExport {
       | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
       | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
         if self.should_remove(local.to_id()) {
+          self.state.report.record_dropped_import(&i.src.value, &local.sym);
           self.state.should_run_again = true;
           false
         } else {
@@ -110,12 +136,14 @@ impl Fold for KeepExport {
   }
 
   fn fold_module(&mut self, mut m: Module) -> Module {
+    self.state.local_exports = collect_export_names(&m.body);
     {
       // Fill the state.
       let mut v = Analyzer {
         state: &mut self.state,
         in_lhs_of_var: false,
         in_kept_fn: false,
+        in_ts_module: false,
       };
       m = m.fold_with(&mut v);
     }
@@ -147,15 +175,32 @@ impl Fold for KeepExport {
 
   fn fold_module_item(&mut self, i: ModuleItem) -> ModuleItem {
     if let ModuleItem::ModuleDecl(ModuleDecl::Import(i)) = i {
+      let is_bare = i.specifiers.is_empty();
       let i = i.fold_with(self);
 
       if i.specifiers.is_empty() {
+        if is_bare && !i.type_only && self.state.keep_module_side_effects {
+          return ModuleItem::ModuleDecl(ModuleDecl::Import(i));
+        }
+        // Keep a bare import of modules with side effects, `import type` is never one.
+        if !i.type_only && self.state.side_effects.has_side_effects(&i.src.value) {
+          self.state.report.record_side_effect_import(&i.src.value);
+          return ModuleItem::ModuleDecl(ModuleDecl::Import(i));
+        }
         return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
       }
 
       return ModuleItem::ModuleDecl(ModuleDecl::Import(i));
     }
 
+    if let ModuleItem::ModuleDecl(ModuleDecl::ExportAll(e)) = &i {
+      let keep = self.state.should_keep_export_all();
+      self.state.report.record_export_all(&e.src.value, keep);
+      if !keep {
+        return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+      }
+    }
+
     let i = i.fold_children_with(self);
 
     match &i {
@@ -216,6 +261,7 @@ impl Fold for KeepExport {
       match &mut p {
         Pat::Ident(name) => {
           if self.should_remove(name.id.to_id()) {
+            self.state.report.record_removed_declaration(&name.id.sym);
             self.state.should_run_again = true;
             return Pat::Invalid(Invalid { span: DUMMY_SP });
           }
@@ -282,6 +328,7 @@ impl Fold for KeepExport {
     match s {
       Stmt::Decl(Decl::Fn(f)) => {
         if self.should_remove(f.ident.to_id()) {
+          self.state.report.record_removed_declaration(&f.ident.sym);
           self.mark_as_candidate(f.function);
           return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
         }
@@ -290,12 +337,33 @@ impl Fold for KeepExport {
       }
       Stmt::Decl(Decl::Class(c)) => {
         if self.should_remove(c.ident.to_id()) {
+          self.state.report.record_removed_declaration(&c.ident.sym);
           self.mark_as_candidate(c.class);
           return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
         }
 
         s = Stmt::Decl(Decl::Class(c));
       }
+      Stmt::Decl(Decl::TsEnum(e)) => {
+        if self.should_remove(e.id.to_id()) {
+          self.state.report.record_removed_declaration(&e.id.sym);
+          self.mark_as_candidate(e.members);
+          return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
+        }
+
+        s = Stmt::Decl(Decl::TsEnum(e));
+      }
+      Stmt::Decl(Decl::TsModule(m)) => {
+        if let TsModuleName::Ident(id) = &m.id {
+          if self.should_remove(id.to_id()) {
+            self.state.report.record_removed_declaration(&id.sym);
+            self.mark_as_candidate(m);
+            return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
+          }
+        }
+
+        s = Stmt::Decl(Decl::TsModule(m));
+      }
       _ => {}
     }
 
@@ -313,6 +381,12 @@ impl Fold for KeepExport {
   /// This method make `name` of [VarDeclarator] to [Pat::Invalid] if it
   /// should be removed.
   fn fold_var_declarator(&mut self, mut d: VarDeclarator) -> VarDeclarator {
+    // The initializer runs for its side effects even if the declared names are not used.
+    if self.state.keep_module_side_effects && d.init.as_deref().is_some_and(may_have_side_effects) {
+      d.init = d.init.fold_with(self);
+      return d;
+    }
+
     let old = self.in_lhs_of_var;
     self.in_lhs_of_var = true;
     let name = d.name.fold_with(self);
@@ -333,12 +407,83 @@ impl Fold for KeepExport {
 
     decls
   }
+
+  /// Members of a namespace are accessed through the namespace object, so a namespace is
+  /// kept or removed as a whole.
+  fn fold_ts_module_decl(&mut self, d: TsModuleDecl) -> TsModuleDecl {
+    d
+  }
+}
+
+/// Identifier of the declaration, the first declarator is used for variables.
+fn get_decl_ident(decl: &Decl) -> Option<&Ident> {
+  match decl {
+    Decl::Fn(f) => Some(&f.ident),
+    Decl::Class(c) => Some(&c.ident),
+    Decl::TsEnum(e) => Some(&e.id),
+    Decl::TsModule(m) => match &m.id {
+      TsModuleName::Ident(id) => Some(id),
+      _ => None,
+    },
+    Decl::TsInterface(i) => Some(&i.id),
+    Decl::TsTypeAlias(t) => Some(&t.id),
+    Decl::Var(d) => match d.decls.first().map(|d| &d.name) {
+      Some(Pat::Ident(id)) => Some(&id.id),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn collect_export_names(items: &[ModuleItem]) -> FxHashSet<String> {
+  let mut names = FxHashSet::default();
+  for item in items {
+    match item {
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        decl: Decl::Var(v), ..
+      })) => {
+        for d in &v.decls {
+          if let Pat::Ident(id) = &d.name {
+            names.insert(id.id.sym.to_string());
+          }
+        }
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) => {
+        if let Some(ident) = get_decl_ident(&e.decl) {
+          names.insert(ident.sym.to_string());
+        }
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e)) => {
+        for s in &e.specifiers {
+          let name = match s {
+            ExportSpecifier::Default(ExportDefaultSpecifier { exported }) => {
+              names.insert(exported.sym.to_string());
+              continue;
+            }
+            ExportSpecifier::Namespace(ExportNamespaceSpecifier { name, .. }) => name,
+            ExportSpecifier::Named(ExportNamedSpecifier {
+              exported: Some(exported),
+              ..
+            }) => exported,
+            ExportSpecifier::Named(ExportNamedSpecifier { orig, .. }) => orig,
+          };
+          if let ModuleExportName::Ident(ident) = name {
+            names.insert(ident.sym.to_string());
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  names
 }
 
 struct Analyzer<'a> {
   state: &'a mut State,
   in_lhs_of_var: bool,
   in_kept_fn: bool,
+  /// Whether the analyzer is inside a namespace, where `export` declares namespace members.
+  in_ts_module: bool,
 }
 
 impl Analyzer<'_> {
@@ -397,26 +542,18 @@ impl Fold for Analyzer<'_> {
   fn fold_export_decl(&mut self, s: ExportDecl) -> ExportDecl {
     let old_in_kept = self.in_kept_fn;
 
-    match &s.decl {
-      Decl::Fn(f) => {
-        if self.state.should_keep_identifier(&f.ident) {
-          self.in_kept_fn = true;
-          self.add_ref(f.ident.to_id());
-        }
+    if let Decl::Var(d) = &s.decl {
+      if d.decls.is_empty() {
+        return s;
       }
-
-      Decl::Var(d) => {
-        if d.decls.is_empty() {
-          return s;
-        }
-        if let Pat::Ident(id) = &d.decls[0].name {
-          if self.state.should_keep_identifier(&id.id) {
-            self.in_kept_fn = true;
-            self.add_ref(id.to_id());
-          }
+    }
+    if !self.in_ts_module {
+      if let Some(ident) = get_decl_ident(&s.decl) {
+        if self.state.should_keep_identifier(ident) {
+          self.in_kept_fn = true;
+          self.add_ref(ident.to_id());
         }
       }
-      _ => {}
     }
     let e = s.fold_children_with(self);
     self.in_kept_fn = old_in_kept;
@@ -469,9 +606,25 @@ impl Fold for Analyzer<'_> {
     f
   }
 
+  fn fold_ts_module_decl(&mut self, d: TsModuleDecl) -> TsModuleDecl {
+    let old_in_ts_module = self.in_ts_module;
+    self.in_ts_module = true;
+    let d = d.fold_children_with(self);
+    self.in_ts_module = old_in_ts_module;
+    d
+  }
+
   /// Drops [ExportDecl] if all specifiers are removed.
   fn fold_module_item(&mut self, s: ModuleItem) -> ModuleItem {
+    if self.in_ts_module {
+      return s.fold_children_with(self);
+    }
+
     match s {
+      // Statements run for their side effects are kept as is, and keep what they reference.
+      ModuleItem::Stmt(Stmt::Expr(_) | Stmt::If(_) | Stmt::DoWhile(_) | Stmt::Try(_))
+        if self.state.keep_module_side_effects => {}
+
       ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(e)) if !e.specifiers.is_empty() => {
         let e = e.fold_with(self);
 
@@ -502,31 +655,25 @@ impl Fold for Analyzer<'_> {
     };
 
     if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) = &s {
-      match &e.decl {
-        Decl::Fn(f) => {
-          if self.state.should_keep_identifier(&f.ident) {
-            let s = s.fold_children_with(self);
-            return s;
-          } else {
-            return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
-          }
+      if let Decl::Var(d) = &e.decl {
+        if d.decls.is_empty() {
+          return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
         }
+      }
 
-        Decl::Var(d) => {
-          if d.decls.is_empty() {
+      if let Some(ident) = get_decl_ident(&e.decl) {
+        if !self.state.should_keep_identifier(ident) {
+          // Types are never referenced at runtime.
+          if matches!(e.decl, Decl::TsInterface(..) | Decl::TsTypeAlias(..)) {
             return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
           }
-
-          if let Pat::Ident(id) = &d.decls[0].name {
-            if self.state.should_keep_identifier(&id.id) {
-              let s = s.fold_children_with(self);
-              return s;
-            } else {
-              return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
-            }
+          // Keep the declaration without `export`, so it is still available to the kept code,
+          // such as a decorator of a kept class. It is removed later if nothing references it.
+          if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(e)) = s {
+            return ModuleItem::Stmt(Stmt::Decl(e.decl));
           }
+          unreachable!();
         }
-        _ => {}
       }
     }
 
@@ -579,14 +726,39 @@ impl Fold for Analyzer<'_> {
   }
 }
 
-pub fn keep_export(exports: Vec<String>) -> impl Pass {
+/// Keep the exports matching `exports` and the code they depend on, what is pruned is
+/// recorded to `report`. Imports of modules with `side_effects` are kept as bare imports.
+///
+/// Exported declarations which are not kept lose their `export`, and are only kept while the
+/// kept code references them, such as a helper called by the kept default export.
+pub fn keep_export(
+  exports: Vec<ExportPattern>,
+  report: ExportReport,
+  side_effects: SideEffects,
+) -> impl Pass {
   fold_pass(
     Repeat::new(KeepExport {
       state: State {
         keep_exports: exports,
+        report,
+        side_effects,
         ..Default::default()
       },
       in_lhs_of_var: false,
     })
   )
 }
+
+/// Keep the exports matching `exports` of a module along with the statements run for their
+/// side effects, such as `import './style.css'` and `console.log()`. It builds what is left of a
+/// barrel file once its re-exports are redirected to their sources.
+pub fn keep_residual_exports(exports: Vec<ExportPattern>) -> impl Pass {
+  fold_pass(Repeat::new(KeepExport {
+    state: State {
+      keep_exports: exports,
+      keep_module_side_effects: true,
+      ..Default::default()
+    },
+    in_lhs_of_var: false,
+  }))
+}
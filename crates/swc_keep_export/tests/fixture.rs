@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use swc_core::ecma::parser::{Syntax, TsSyntax};
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_keep_export::{keep_export, keep_residual_exports, ExportReport, SideEffects};
+
+fn ts_syntax() -> Syntax {
+  Syntax::Typescript(TsSyntax {
+    decorators: true,
+    ..Default::default()
+  })
+}
+
+#[testing::fixture("tests/fixture/export_all/input.js")]
+fn test_export_all(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_export(
+        vec!["pageConfig".into(), "utils".into()],
+        ExportReport::default(),
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/export_all_reexported/input.js")]
+fn test_export_all_reexported(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_export(
+        vec!["dataLoader".into()],
+        ExportReport::default(),
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/ts_enum_namespace/input.ts")]
+fn test_ts_enum_namespace(input: PathBuf) {
+  let output = input.with_file_name("output.ts");
+  test_fixture(
+    ts_syntax(),
+    &|_t| {
+      keep_export(
+        vec!["pageConfig".into()],
+        ExportReport::default(),
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/decorator_references/input.ts")]
+fn test_decorator_references(input: PathBuf) {
+  let output = input.with_file_name("output.ts");
+  test_fixture(
+    ts_syntax(),
+    &|_t| {
+      keep_export(
+        vec!["PageConfig".into()],
+        ExportReport::default(),
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+// Exported declarations which are not kept are only kept without `export` while the kept code
+// references them, unreferenced ones are removed along with their imports.
+#[testing::fixture("tests/fixture/unexported_declarations/input.js")]
+fn test_unexported_declarations(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_export(
+        vec!["default".into()],
+        ExportReport::default(),
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/side_effect_imports/input.js")]
+fn test_side_effect_imports(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      keep_export(
+        vec!["pageConfig".into()],
+        ExportReport::default(),
+        SideEffects::new(|src| src == "./polyfill" || src.ends_with(".css")),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/residual_side_effects/input.js")]
+fn test_residual_side_effects(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| keep_residual_exports(vec!["VERSION".into()]),
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
@@ -0,0 +1,18 @@
+import { observer } from 'mobx-react';
+import { track } from './track';
+
+export const pageName = 'home';
+
+function withLayout(layout: string) {
+  return (target: any) => target;
+}
+
+export function getServerData() {
+  return fetch('/api');
+}
+
+@withLayout(pageName)
+export class PageConfig {
+  @track
+  render() {}
+}
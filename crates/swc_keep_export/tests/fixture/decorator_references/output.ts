@@ -0,0 +1,10 @@
+import { track } from './track';
+const pageName = 'home';
+function withLayout(layout: string) {
+  return (target: any) => target;
+}
+@withLayout(pageName)
+export class PageConfig {
+  @track
+  render() {}
+}
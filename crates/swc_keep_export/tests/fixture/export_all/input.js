@@ -0,0 +1,9 @@
+export * from './config';
+export * as utils from './utils';
+export * as data from './data';
+
+export const pageConfig = () => ({
+  title: 'Home',
+});
+
+export default function Home() {}
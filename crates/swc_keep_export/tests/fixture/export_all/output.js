@@ -0,0 +1,4 @@
+export * as utils from './utils';
+export const pageConfig = () => ({
+  title: 'Home',
+});
@@ -0,0 +1,3 @@
+export * from './loaders';
+
+export const pageConfig = {};
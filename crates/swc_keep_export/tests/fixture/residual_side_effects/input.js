@@ -0,0 +1,12 @@
+import './x.css';
+import { logger } from './logger';
+import { Button } from './button';
+const store = createStore();
+const sizes = ['small', 'large'];
+console.log('loaded');
+if (typeof window !== 'undefined') {
+  logger.info(store);
+}
+export { Button };
+export const VERSION = '1.0';
+export const theme = createTheme();
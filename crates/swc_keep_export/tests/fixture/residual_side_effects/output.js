@@ -0,0 +1,9 @@
+import './x.css';
+import { logger } from './logger';
+const store = createStore();
+console.log('loaded');
+if (typeof window !== 'undefined') {
+  logger.info(store);
+}
+export const VERSION = '1.0';
+const theme = createTheme();
@@ -0,0 +1,13 @@
+import { register } from './polyfill';
+import { request } from './request';
+import './index.css';
+import './setup';
+
+export const pageConfig = () => ({
+  title: 'Home',
+});
+
+export function dataLoader() {
+  register();
+  return request('/api');
+}
@@ -0,0 +1,5 @@
+import './polyfill';
+import './index.css';
+export const pageConfig = () => ({
+  title: 'Home',
+});
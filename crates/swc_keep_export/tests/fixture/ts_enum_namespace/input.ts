@@ -0,0 +1,23 @@
+enum Theme {
+  Light = 'light',
+  Dark = 'dark',
+}
+
+enum Unused {
+  A,
+}
+
+export namespace Routes {
+  export const home = '/';
+  export const about = '/about';
+}
+
+export enum Layout {
+  Default,
+  Blank,
+}
+
+export const pageConfig = () => ({
+  theme: Theme.Dark,
+  path: Routes.home,
+});
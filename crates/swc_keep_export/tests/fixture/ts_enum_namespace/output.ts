@@ -0,0 +1,12 @@
+enum Theme {
+  Light = 'light',
+  Dark = 'dark',
+}
+namespace Routes {
+  export const home = '/';
+  export const about = '/about';
+}
+export const pageConfig = () => ({
+  theme: Theme.Dark,
+  path: Routes.home,
+});
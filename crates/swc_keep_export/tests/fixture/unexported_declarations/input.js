@@ -0,0 +1,16 @@
+import { format } from './format';
+import { request } from './request';
+
+export function formatDate(date) {
+  return format(date);
+}
+
+export const getData = () => request('/api');
+
+export function getConfig() {
+  return { title: 'Home' };
+}
+
+export default function Home() {
+  return formatDate(new Date());
+}
@@ -0,0 +1,7 @@
+import { format } from './format';
+function formatDate(date) {
+  return format(date);
+}
+export default function Home() {
+  return formatDate(new Date());
+}
@@ -0,0 +1,63 @@
+use swc_keep_export::ExportPattern;
+
+#[test]
+fn test_plain_name() {
+  let pattern = ExportPattern::parse("pageConfig").unwrap();
+  assert!(matches!(pattern, ExportPattern::Name(_)));
+  assert!(pattern.matches("pageConfig"));
+  assert!(!pattern.matches("pageConfigs"));
+  assert!(!pattern.matches("PageConfig"));
+}
+
+#[test]
+fn test_glob() {
+  let pattern = ExportPattern::parse("get*Config").unwrap();
+  assert!(matches!(pattern, ExportPattern::Glob(_)));
+  assert!(pattern.matches("getConfig"));
+  assert!(pattern.matches("getPageConfig"));
+  assert!(!pattern.matches("pageConfig"));
+
+  let pattern = ExportPattern::parse("data[A-Z]oader").unwrap();
+  assert!(pattern.matches("dataLoader"));
+  assert!(!pattern.matches("dataloader"));
+}
+
+#[test]
+fn test_invalid_glob() {
+  let err = ExportPattern::parse("data[Loader").unwrap_err();
+  assert!(err.contains("invalid export name glob `data[Loader`"));
+}
+
+#[test]
+fn test_regex() {
+  let pattern = ExportPattern::parse("/^(server|static)DataLoader$/").unwrap();
+  assert!(matches!(pattern, ExportPattern::Regex(_)));
+  assert!(pattern.matches("serverDataLoader"));
+  assert!(pattern.matches("staticDataLoader"));
+  assert!(!pattern.matches("dataLoader"));
+  assert!(!pattern.matches("ServerDataLoader"));
+}
+
+#[test]
+fn test_regex_flags() {
+  let pattern = ExportPattern::parse("/^dataloader$/i").unwrap();
+  assert!(pattern.matches("dataLoader"));
+  assert!(pattern.matches("DATALOADER"));
+
+  let err = ExportPattern::parse("/^dataLoader$/g").unwrap_err();
+  assert!(err.contains("invalid flag `g`"));
+}
+
+#[test]
+fn test_invalid_regex() {
+  let err = ExportPattern::parse("/(dataLoader/").unwrap_err();
+  assert!(err.contains("invalid export name regex `/(dataLoader/`"));
+}
+
+#[test]
+fn test_name_with_slash() {
+  // Only `/source/flags` is a regex, a single leading slash is not.
+  let pattern = ExportPattern::parse("/dataLoader").unwrap();
+  assert!(matches!(pattern, ExportPattern::Name(_)));
+  assert!(pattern.matches("/dataLoader"));
+}
@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_keep_export::{keep_export, ExportPattern, ExportReport, ExportSummary, SideEffects};
+
+fn summarize(fixture: &str, exports: Vec<ExportPattern>) -> ExportSummary {
+  let input = Path::new("tests/fixture").join(fixture).join("input.js");
+  let output = input.with_file_name("output.js");
+  let report = ExportReport::default();
+  test_fixture(
+    Default::default(),
+    &|_t| keep_export(exports.clone(), report.clone(), SideEffects::default()),
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+  report.summary()
+}
+
+#[test]
+fn test_export_all_reported_separately() {
+  let summary = summarize("export_all", vec!["pageConfig".into(), "utils".into()]);
+  assert_eq!(summary.export_all, BTreeMap::from([("./config".to_string(), false)]));
+  assert!(summary.kept.iter().all(|name| !name.starts_with('*')));
+  assert!(summary.removed.iter().all(|name| !name.starts_with('*')));
+  assert!(summary.kept.contains("pageConfig"));
+  assert!(summary.kept.contains("utils"));
+  assert!(summary.removed.contains("data"));
+  assert!(summary.removed.contains("default"));
+}
+
+#[test]
+fn test_kept_export_all() {
+  let summary = summarize("export_all_reexported", vec!["dataLoader".into()]);
+  assert_eq!(summary.export_all, BTreeMap::from([("./loaders".to_string(), true)]));
+  assert!(summary.removed.contains("pageConfig"));
+}
+
+#[test]
+fn test_merge_export_all() {
+  let mut summary = ExportSummary::default();
+  summary.merge(ExportSummary {
+    export_all: BTreeMap::from([("./a".to_string(), true)]),
+    ..Default::default()
+  });
+  assert!(!summary.is_empty());
+  assert_eq!(summary.export_all.get("./a"), Some(&true));
+}
+
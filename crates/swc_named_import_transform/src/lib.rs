@@ -1,8 +1,13 @@
-use std::collections::HashSet;
+#![feature(let_chains)]
+
+use std::collections::{HashMap, HashSet};
 
 use swc_core::{
   common::DUMMY_SP,
-  ecma::{ast::*, visit::{fold_pass, Fold}},
+  ecma::{
+    ast::*,
+    visit::{fold_pass, noop_visit_type, Fold, FoldWith, Visit, VisitWith},
+  },
 };
 
 pub struct TransformConfig {
@@ -11,67 +16,302 @@ pub struct TransformConfig {
 
 pub struct NamedImportTransform {
   pub packages: Vec<String>,
+  // Member names accessed on namespace imports, `None` if the namespace escapes.
+  namespace_usages: HashMap<Id, Option<HashSet<String>>>,
 }
 
+/// Rewrite imports of `config.packages` to import only the used names from the barrel file.
+///
+/// Usages of namespace imports and dynamic import results are matched by [Id], so the program
+/// must be processed by `resolver` first to tell shadowed names apart. swc runs `resolver`
+/// before the custom passes of the loader.
 pub fn named_import_transform(config: TransformConfig) -> impl Pass {
   fold_pass(NamedImportTransform {
     packages: config.packages,
+    namespace_usages: Default::default(),
   })
 }
 
 impl Fold for NamedImportTransform {
+  fn fold_module(&mut self, module: Module) -> Module {
+    // Collect namespace imports of the optimized packages before rewriting imports.
+    for item in &module.body {
+      if let ModuleItem::ModuleDecl(ModuleDecl::Import(decl)) = item
+        && self.is_optimized_package(&decl.src.value)
+      {
+        for specifier in &decl.specifiers {
+          if let ImportSpecifier::Namespace(specifier) = specifier {
+            self
+              .namespace_usages
+              .insert(specifier.local.to_id(), Some(HashSet::new()));
+          }
+        }
+      }
+    }
+    if !self.namespace_usages.is_empty() {
+      let mut collector = NamespaceUsageCollector {
+        usages: &mut self.namespace_usages,
+      };
+      module.visit_with(&mut collector);
+    }
+    module.fold_children_with(self)
+  }
+
   fn fold_import_decl(&mut self, decl: ImportDecl) -> ImportDecl {
     let src_value = decl.src.value.clone();
-    if self.packages.iter().any(|p| src_value == *p) {
+    if self.is_optimized_package(&src_value) {
       let mut specifier_names = HashSet::new();
       let mut skip = false;
       for specifier in &decl.specifiers {
         match specifier {
           ImportSpecifier::Named(specifier) => {
             if let Some(imported) = &specifier.imported {
-              match imported {
-                ModuleExportName::Ident(ident) => {
-                  specifier_names.insert(ident.sym.to_string());
-                }
-                ModuleExportName::Str(str) => {
-                  specifier_names.insert(str.value.to_string());
-                }
-              }
+              specifier_names.insert(export_name(imported));
             } else {
               specifier_names.insert(specifier.local.sym.to_string());
             }
           }
           ImportSpecifier::Default(_) => {
-            skip = true;
+            specifier_names.insert(String::from("default"));
           }
-          ImportSpecifier::Namespace(_) => {
-            skip = true;
+          ImportSpecifier::Namespace(specifier) => {
+            // Only optimize namespace imports whose members are statically accessed.
+            match self.namespace_usages.get(&specifier.local.to_id()) {
+              Some(Some(names)) if !names.is_empty() => {
+                specifier_names.extend(names.iter().cloned());
+              }
+              _ => {
+                skip = true;
+              }
+            }
           }
         }
       }
-      if !skip {
-        let mut names = specifier_names.into_iter().collect::<Vec<_>>();
-        names.sort();
-
-        let new_src = format!(
-          // Add unique query string to avoid loader cache.
-          "__barrel_optimize__?names={}!=!{}?{}",
-          names.join(","),
-          src_value,
-          names.join(","),
-        );
-
+      if !skip && !specifier_names.is_empty() {
         // Create a new import declaration, keep everything the same except the source
         let mut new_decl = decl.clone();
-        new_decl.src = Box::new(Str {
-          span: DUMMY_SP,
-          value: new_src.into(),
-          raw: None,
-        });
-
+        new_decl.src = Box::new(create_barrel_src(&src_value, specifier_names));
         return new_decl;
       }
     }
     decl
   }
+
+  fn fold_named_export(&mut self, export: NamedExport) -> NamedExport {
+    // export { Button, default as Icon } from 'antd';
+    if let Some(src) = &export.src
+      && self.is_optimized_package(&src.value)
+    {
+      let mut specifier_names = HashSet::new();
+      for specifier in &export.specifiers {
+        match specifier {
+          ExportSpecifier::Named(specifier) => {
+            specifier_names.insert(export_name(&specifier.orig));
+          }
+          ExportSpecifier::Default(_) => {
+            specifier_names.insert(String::from("default"));
+          }
+          // `export * as ns from 'antd'` needs the whole package.
+          ExportSpecifier::Namespace(_) => return export,
+        }
+      }
+      if !specifier_names.is_empty() {
+        let mut new_export = export.clone();
+        new_export.src = Some(Box::new(create_barrel_src(&src.value, specifier_names)));
+        return new_export;
+      }
+    }
+    export
+  }
+
+  fn fold_call_expr(&mut self, call: CallExpr) -> CallExpr {
+    let mut call = call.fold_children_with(self);
+    // import('antd').then(({ Button }) => {}) or import('antd').then((m) => m.Button)
+    if let Callee::Expr(callee) = &mut call.callee
+      && let Expr::Member(MemberExpr { obj, prop, .. }) = &mut **callee
+      && matches!(prop, MemberProp::Ident(prop) if prop.sym == "then")
+      && let Some(arg) = call.args.first()
+      && arg.spread.is_none()
+      && let Some(names) = get_callback_names(&arg.expr)
+    {
+      self.rewrite_dynamic_import(obj, names);
+    }
+    call
+  }
+
+  fn fold_var_declarator(&mut self, declarator: VarDeclarator) -> VarDeclarator {
+    let mut declarator = declarator.fold_children_with(self);
+    // const { Button } = await import('antd');
+    if let Some(names) = get_object_pat_names(&declarator.name)
+      && let Some(init) = &mut declarator.init
+      && let Expr::Await(AwaitExpr { arg, .. }) = &mut **init
+    {
+      self.rewrite_dynamic_import(arg, names);
+    }
+    declarator
+  }
+}
+
+impl NamedImportTransform {
+  fn is_optimized_package(&self, src: &str) -> bool {
+    self.packages.iter().any(|p| src == p)
+  }
+
+  /// Rewrite the source of `import('antd')` when only `names` are used from it.
+  fn rewrite_dynamic_import(&self, expr: &mut Expr, names: HashSet<String>) {
+    if names.is_empty() {
+      return;
+    }
+    if let Expr::Paren(ParenExpr { expr, .. }) = expr {
+      return self.rewrite_dynamic_import(expr, names);
+    }
+    if let Expr::Call(CallExpr {
+      callee: Callee::Import(_),
+      args,
+      ..
+    }) = expr
+      && args.len() == 1
+      && args[0].spread.is_none()
+      && let Expr::Lit(Lit::Str(src)) = &*args[0].expr
+      && self.is_optimized_package(&src.value)
+    {
+      let new_src = create_barrel_src(&src.value, names);
+      args[0].expr = Box::new(Expr::Lit(Lit::Str(new_src)));
+    }
+  }
+}
+
+fn create_barrel_src(src: &str, names: HashSet<String>) -> Str {
+  let mut names = names.into_iter().collect::<Vec<_>>();
+  names.sort();
+
+  let new_src = format!(
+    // Add unique query string to avoid loader cache.
+    "__barrel_optimize__?names={}!=!{}?{}",
+    names.join(","),
+    src,
+    names.join(","),
+  );
+  Str {
+    span: DUMMY_SP,
+    value: new_src.into(),
+    raw: None,
+  }
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(str) => str.value.to_string(),
+  }
+}
+
+/// Get the names destructured by `{ Button, Input: MyInput }`, `None` if the pattern has a rest element.
+fn get_object_pat_names(pat: &Pat) -> Option<HashSet<String>> {
+  let Pat::Object(object) = pat else {
+    return None;
+  };
+  let mut names = HashSet::new();
+  for prop in &object.props {
+    match prop {
+      ObjectPatProp::Assign(prop) => {
+        names.insert(prop.key.sym.to_string());
+      }
+      ObjectPatProp::KeyValue(prop) => match &prop.key {
+        PropName::Ident(ident) => {
+          names.insert(ident.sym.to_string());
+        }
+        PropName::Str(str) => {
+          names.insert(str.value.to_string());
+        }
+        _ => return None,
+      },
+      ObjectPatProp::Rest(_) => return None,
+    }
+  }
+  Some(names)
+}
+
+/// Get the names used from the module by the `then` callback of a dynamic import.
+fn get_callback_names(callback: &Expr) -> Option<HashSet<String>> {
+  let param = match callback {
+    Expr::Arrow(ArrowExpr { params, .. }) if params.len() == 1 => &params[0],
+    Expr::Fn(FnExpr { function, .. }) if function.params.len() == 1 => &function.params[0].pat,
+    _ => return None,
+  };
+  let Pat::Ident(ident) = param else {
+    return get_object_pat_names(param);
+  };
+  // The module object must only be used for static member access, such as `m.Button`.
+  let id = ident.id.to_id();
+  let mut usages = HashMap::from([(id.clone(), Some(HashSet::new()))]);
+  let mut collector = NamespaceUsageCollector {
+    usages: &mut usages,
+  };
+  match callback {
+    Expr::Arrow(arrow) => arrow.body.visit_with(&mut collector),
+    Expr::Fn(FnExpr { function, .. }) => function.body.visit_with(&mut collector),
+    _ => {}
+  }
+  usages.remove(&id).flatten()
+}
+
+struct NamespaceUsageCollector<'a> {
+  usages: &'a mut HashMap<Id, Option<HashSet<String>>>,
+}
+
+impl NamespaceUsageCollector<'_> {
+  fn add_usage(&mut self, id: Id, name: Option<String>) {
+    if let Some(usage) = self.usages.get_mut(&id) {
+      match (usage.as_mut(), name) {
+        (Some(names), Some(name)) => {
+          names.insert(name);
+        }
+        // The namespace escapes, such as `foo(Icons)` or `Icons[key]`.
+        _ => {
+          *usage = None;
+        }
+      }
+    }
+  }
+}
+
+impl Visit for NamespaceUsageCollector<'_> {
+  // Usages in types are removed after compilation.
+  noop_visit_type!();
+
+  // Skip the import specifiers themselves.
+  fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+  fn visit_member_expr(&mut self, member: &MemberExpr) {
+    if let Expr::Ident(obj) = &*member.obj
+      && self.usages.contains_key(&obj.to_id())
+    {
+      let name = match &member.prop {
+        MemberProp::Ident(prop) => Some(prop.sym.to_string()),
+        MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+          Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+          _ => None,
+        },
+        _ => None,
+      };
+      self.add_usage(obj.to_id(), name);
+      member.prop.visit_with(self);
+      return;
+    }
+    member.visit_children_with(self);
+  }
+
+  fn visit_jsx_member_expr(&mut self, member: &JSXMemberExpr) {
+    // <Icons.Add />
+    if let JSXObject::Ident(obj) = &member.obj {
+      self.add_usage(obj.to_id(), Some(member.prop.sym.to_string()));
+      return;
+    }
+    member.visit_children_with(self);
+  }
+
+  fn visit_ident(&mut self, ident: &Ident) {
+    self.add_usage(ident.to_id(), None);
+  }
 }
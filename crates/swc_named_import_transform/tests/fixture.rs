@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use swc_core::common::Mark;
+use swc_core::ecma::parser::{EsSyntax, Syntax};
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_named_import_transform::{named_import_transform, TransformConfig};
+
+// Options of a fixture, read from `config.json` next to the input.
+#[derive(Deserialize)]
+#[serde(default)]
+struct FixtureConfig {
+  packages: Vec<String>,
+}
+
+impl Default for FixtureConfig {
+  fn default() -> Self {
+    Self {
+      packages: vec![String::from("antd")],
+    }
+  }
+}
+
+#[testing::fixture("tests/fixture/**/input.js")]
+fn fixture(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  let config = match fs::read_to_string(input.with_file_name("config.json")) {
+    Ok(json) => serde_json::from_str::<FixtureConfig>(&json).unwrap(),
+    Err(_) => FixtureConfig::default(),
+  };
+  test_fixture(
+    Syntax::Es(EsSyntax {
+      jsx: true,
+      ..Default::default()
+    }),
+    &|_t| {
+      // The loader runs `resolver` before the transform, see `named_import_transform`.
+      (
+        resolver(Mark::new(), Mark::new(), false),
+        named_import_transform(TransformConfig {
+          packages: config.packages.clone(),
+        }),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
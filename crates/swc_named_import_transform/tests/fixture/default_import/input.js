@@ -0,0 +1,3 @@
+import Antd, { Button } from 'antd';
+import Other from 'other';
+console.log(Antd, Button, Other);
@@ -0,0 +1,3 @@
+import Antd, { Button } from "__barrel_optimize__?names=Button,default!=!antd?Button,default";
+import Other from 'other';
+console.log(Antd, Button, Other);
@@ -0,0 +1,10 @@
+import('antd').then(({ Button, Input: TextInput }) => {
+  console.log(Button, TextInput);
+});
+import('antd').then((m) => m.Select);
+import('antd').then((m) => console.log(m));
+async function load() {
+  const { Table } = await import('antd');
+  const { Modal, ...rest } = await import('antd');
+  return [Table, Modal, rest];
+}
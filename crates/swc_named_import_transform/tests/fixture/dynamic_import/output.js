@@ -0,0 +1,14 @@
+import("__barrel_optimize__?names=Button,Input!=!antd?Button,Input").then(({ Button, Input: TextInput })=>{
+    console.log(Button, TextInput);
+});
+import("__barrel_optimize__?names=Select!=!antd?Select").then((m)=>m.Select);
+import('antd').then((m)=>console.log(m));
+async function load() {
+    const { Table } = await import("__barrel_optimize__?names=Table!=!antd?Table");
+    const { Modal, ...rest } = await import('antd');
+    return [
+        Table,
+        Modal,
+        rest
+    ];
+}
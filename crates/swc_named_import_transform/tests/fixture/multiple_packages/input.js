@@ -0,0 +1,4 @@
+import { Button } from 'antd';
+import { Card, List } from '@ice/components';
+import { Other } from 'other';
+console.log(Button, Card, List, Other);
@@ -0,0 +1,4 @@
+import { Button } from "__barrel_optimize__?names=Button!=!antd?Button";
+import { Card, List } from "__barrel_optimize__?names=Card,List!=!@ice/components?Card,List";
+import { Other } from 'other';
+console.log(Button, Card, List, Other);
@@ -0,0 +1,5 @@
+import * as Icons from 'antd';
+import * as Components from 'antd';
+console.log(Icons.Add);
+register(Icons);
+console.log(Components[name]);
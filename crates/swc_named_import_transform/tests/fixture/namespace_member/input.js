@@ -0,0 +1,5 @@
+import * as Icons from 'antd';
+function render(Icons) {
+  return Icons.Unknown;
+}
+console.log(Icons.Add, Icons['Remove'], <Icons.Edit />, render);
@@ -0,0 +1,5 @@
+import * as Icons from "__barrel_optimize__?names=Add,Edit,Remove!=!antd?Add,Edit,Remove";
+function render(Icons) {
+    return Icons.Unknown;
+}
+console.log(Icons.Add, Icons['Remove'], <Icons.Edit/>, render);
@@ -0,0 +1,4 @@
+export { Button, Input as TextInput } from 'antd';
+export { default as Icon } from 'antd';
+export * as Antd from 'antd';
+export { Select } from 'other';
@@ -0,0 +1,4 @@
+export { Button, Input as TextInput } from "__barrel_optimize__?names=Button,Input!=!antd?Button,Input";
+export { default as Icon } from "__barrel_optimize__?names=default!=!antd?default";
+export * as Antd from 'antd';
+export { Select } from 'other';
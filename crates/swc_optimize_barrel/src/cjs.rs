@@ -0,0 +1,379 @@
+use std::collections::{HashMap, HashSet};
+
+use swc_core::{common::Spanned, ecma::ast::*};
+
+use crate::{BarrelAnalysis, Config, ExportEntry};
+
+/// What a CommonJS expression refers to: ("source module", "orig ident").
+type ExportRef = (String, String);
+
+/// Collect exports of CommonJS barrel files, such as:
+///
+/// ```js
+/// module.exports = { Button: require('./button') };
+/// exports.Input = require('./input').Input;
+/// Object.defineProperty(exports, 'Select', { enumerable: true, get: function () { return _select.default; } });
+/// ```
+pub(crate) fn analyze_cjs_barrel(module: &Module, config: &Config) -> Option<BarrelAnalysis> {
+  let mut analyzer = CjsAnalyzer {
+    wildcard: config.wildcard,
+    ..Default::default()
+  };
+
+  // Babel declares the `require` locals after the `Object.defineProperty` calls using them,
+  // so collect them in a pre-pass.
+  for item in &module.body {
+    if let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) = item {
+      analyzer.collect_requires(var);
+    }
+  }
+
+  let mut allowed_directives = true;
+  for item in &module.body {
+    // ESM files are handled by `analyze_barrel`.
+    let ModuleItem::Stmt(stmt) = item else {
+      return None;
+    };
+    if let Stmt::Expr(ExprStmt { expr, .. }) = stmt
+      && let Expr::Lit(Lit::Str(s)) = &**expr
+    {
+      if allowed_directives && s.value.starts_with("use ") {
+        analyzer.directives.push(s.value.to_string());
+      }
+      continue;
+    }
+    allowed_directives = false;
+    if !analyzer.visit_stmt(stmt) && !config.wildcard {
+      return None;
+    }
+  }
+
+  // Helpers such as `_interopRequireDefault` are only allowed when they wrap `require` calls.
+  if !config.wildcard
+    && analyzer
+      .declared_helpers
+      .iter()
+      .any(|helper| !analyzer.is_allowed_helper(helper))
+  {
+    return None;
+  }
+
+  // Files such as `exports.__esModule = true;` alone export nothing to redirect.
+  if analyzer.exports.is_empty() && analyzer.wildcard_exports.is_empty() {
+    return None;
+  }
+
+  Some(BarrelAnalysis {
+    exports: analyzer.exports,
+    wildcard_exports: analyzer.wildcard_exports,
+    directives: analyzer.directives,
+    side_effects: false,
+  })
+}
+
+#[derive(Debug, Default)]
+struct CjsAnalyzer {
+  wildcard: bool,
+  exports: Vec<ExportEntry>,
+  wildcard_exports: Vec<String>,
+  directives: Vec<String>,
+  // Map of "local ident" -> ("source module", "orig ident"), e.g. `var _button = require('./button')`.
+  local_requires: HashMap<String, ExportRef>,
+  // Functions wrapping `require` calls, such as `_interopRequireDefault(require('./a'))`
+  // and `__exportStar(require('./a'), exports)`.
+  interop_helpers: HashSet<String>,
+  // Top level declarations which are not `require` calls.
+  declared_helpers: Vec<String>,
+}
+
+impl CjsAnalyzer {
+  fn collect_requires(&mut self, var: &VarDecl) {
+    for decl in &var.decls {
+      if let Pat::Ident(BindingIdent { id, .. }) = &decl.name
+        && let Some(init) = decl.init.as_deref()
+        && let Some(export_ref) = self.get_ref(init)
+      {
+        self.local_requires.insert(id.sym.to_string(), export_ref);
+      }
+    }
+  }
+
+  /// Returns `false` if the statement is not allowed in a barrel file.
+  fn visit_stmt(&mut self, stmt: &Stmt) -> bool {
+    match stmt {
+      Stmt::Decl(Decl::Var(var)) => {
+        for decl in &var.decls {
+          let Pat::Ident(BindingIdent { id, .. }) = &decl.name else {
+            return false;
+          };
+          // Checked once all the interop helpers are known.
+          if !self.local_requires.contains_key(&*id.sym) {
+            self.declared_helpers.push(id.sym.to_string());
+          }
+        }
+        true
+      }
+      Stmt::Decl(Decl::Fn(func)) => {
+        self.declared_helpers.push(func.ident.sym.to_string());
+        true
+      }
+      Stmt::Expr(ExprStmt { expr, .. }) => self.visit_expr(expr),
+      _ => false,
+    }
+  }
+
+  fn visit_expr(&mut self, expr: &Expr) -> bool {
+    match expr {
+      Expr::Seq(seq) => seq.exprs.iter().all(|expr| self.visit_expr(expr)),
+      Expr::Paren(paren) => self.visit_expr(&paren.expr),
+      Expr::Assign(assign) if assign.op == AssignOp::Assign => {
+        let AssignTarget::Simple(SimpleAssignTarget::Member(member)) = &assign.left else {
+          return false;
+        };
+        if is_module_exports(member) {
+          // module.exports = require('./lib');
+          if let Some((src, orig)) = self.get_ref(&assign.right)
+            && orig == "*"
+          {
+            self.wildcard_exports.push(src);
+            return true;
+          }
+          // module.exports = { Button: require('./button') };
+          if let Expr::Object(object) = &*assign.right {
+            return self.visit_object_lit(object);
+          }
+          return false;
+        }
+        match get_exports_property(member) {
+          // exports.__esModule = true;
+          Some(name) if name == "__esModule" => true,
+          Some(name) => self.add_export(name, &assign.right),
+          None => false,
+        }
+      }
+      Expr::Call(call) => self.visit_call_expr(call),
+      _ => false,
+    }
+  }
+
+  fn visit_call_expr(&mut self, call: &CallExpr) -> bool {
+    let Callee::Expr(callee) = &call.callee else {
+      return false;
+    };
+    // Object.defineProperty(exports, 'Button', { enumerable: true, get: function () { return _button.default; } });
+    if let Expr::Member(member) = &**callee
+      && is_ident(&member.obj, "Object")
+      && member_prop_name(&member.prop).as_deref() == Some("defineProperty")
+      && call.args.len() == 3
+      && is_exports(&call.args[0].expr)
+      && let Expr::Lit(Lit::Str(name)) = &*call.args[1].expr
+      && let Expr::Object(descriptor) = &*call.args[2].expr
+    {
+      if name.value == "__esModule" {
+        return true;
+      }
+      return match get_descriptor_value(descriptor) {
+        Some(value) => self.add_export(name.value.to_string(), value),
+        None => false,
+      };
+    }
+    // __exportStar(require('./button'), exports);
+    if call.args.len() == 2
+      && is_exports(&call.args[1].expr)
+      && let Some((src, orig)) = self.get_ref(&call.args[0].expr)
+      && orig == "*"
+    {
+      if let Expr::Ident(helper) = &**callee {
+        self.interop_helpers.insert(helper.sym.to_string());
+      }
+      self.wildcard_exports.push(src);
+      return true;
+    }
+    false
+  }
+
+  fn visit_object_lit(&mut self, object: &ObjectLit) -> bool {
+    for prop in &object.props {
+      let is_barrel = match prop {
+        PropOrSpread::Spread(spread) => match self.get_ref(&spread.expr) {
+          Some((src, orig)) if orig == "*" => {
+            self.wildcard_exports.push(src);
+            true
+          }
+          _ => false,
+        },
+        PropOrSpread::Prop(prop) => match &**prop {
+          Prop::KeyValue(KeyValueProp { key, value }) => match prop_name(key) {
+            Some(name) => self.add_export(name, value),
+            None => false,
+          },
+          Prop::Shorthand(ident) => {
+            self.add_export(ident.sym.to_string(), &Expr::Ident(ident.clone()))
+          }
+          _ => false,
+        },
+      };
+      if !is_barrel && !self.wildcard {
+        return false;
+      }
+    }
+    true
+  }
+
+  fn add_export(&mut self, name: String, value: &Expr) -> bool {
+    match self.get_ref(value) {
+      // `{ Button: require('./button') }` exports the whole module, like `export * as Button`.
+      Some((src, orig)) => {
+        self.exports.push(ExportEntry {
+          name,
+          src,
+          orig,
+          span: value.span(),
+        });
+        true
+      }
+      None if self.wildcard => {
+        self.exports.push(ExportEntry {
+          name,
+          src: "".into(),
+          orig: "".into(),
+          span: value.span(),
+        });
+        true
+      }
+      None => false,
+    }
+  }
+
+  fn is_allowed_helper(&self, helper: &str) -> bool {
+    if self.interop_helpers.contains(helper) {
+      return true;
+    }
+    // TypeScript helpers only called by `__exportStar` and `__importStar`.
+    matches!(helper, "__createBinding" | "__setModuleDefault")
+      && (self.interop_helpers.contains("__exportStar")
+        || self.interop_helpers.contains("__importStar"))
+  }
+
+  /// Resolve `require('./a')`, `require('./a').b`, `_interop(require('./a'))` and their local aliases.
+  fn get_ref(&mut self, expr: &Expr) -> Option<ExportRef> {
+    match expr {
+      Expr::Paren(paren) => self.get_ref(&paren.expr),
+      Expr::Ident(ident) => self.local_requires.get(&*ident.sym).cloned(),
+      Expr::Call(call) => {
+        if let Some(src) = get_require_source(call) {
+          return Some((src, "*".into()));
+        }
+        // _interopRequireDefault(require('./a')), __importStar(require('./a')) or __importDefault(a_1)
+        if let Callee::Expr(callee) = &call.callee
+          && let Expr::Ident(helper) = &**callee
+          && call.args.len() == 1
+          && call.args[0].spread.is_none()
+          && let Some((src, orig)) = self.get_ref(&call.args[0].expr)
+          && orig == "*"
+        {
+          self.interop_helpers.insert(helper.sym.to_string());
+          return Some((src, "*".into()));
+        }
+        None
+      }
+      Expr::Member(member) => {
+        let (src, orig) = self.get_ref(&member.obj)?;
+        if orig != "*" {
+          return None;
+        }
+        Some((src, member_prop_name(&member.prop)?))
+      }
+      _ => None,
+    }
+  }
+}
+
+fn get_require_source(call: &CallExpr) -> Option<String> {
+  if let Callee::Expr(callee) = &call.callee
+    && is_ident(callee, "require")
+    && call.args.len() == 1
+    && call.args[0].spread.is_none()
+    && let Expr::Lit(Lit::Str(src)) = &*call.args[0].expr
+  {
+    return Some(src.value.to_string());
+  }
+  None
+}
+
+fn get_descriptor_value(descriptor: &ObjectLit) -> Option<&Expr> {
+  descriptor.props.iter().find_map(|prop| {
+    let PropOrSpread::Prop(prop) = prop else {
+      return None;
+    };
+    match &**prop {
+      Prop::KeyValue(KeyValueProp { key, value }) => match prop_name(key)?.as_str() {
+        "value" => Some(&**value),
+        "get" => match &**value {
+          Expr::Fn(f) => get_return_value(&f.function.body.as_ref()?.stmts),
+          Expr::Arrow(arrow) => match &*arrow.body {
+            BlockStmtOrExpr::Expr(expr) => Some(&**expr),
+            BlockStmtOrExpr::BlockStmt(block) => get_return_value(&block.stmts),
+          },
+          _ => None,
+        },
+        _ => None,
+      },
+      Prop::Method(MethodProp { key, function }) if prop_name(key).as_deref() == Some("get") => {
+        get_return_value(&function.body.as_ref()?.stmts)
+      }
+      _ => None,
+    }
+  })
+}
+
+fn get_return_value(stmts: &[Stmt]) -> Option<&Expr> {
+  match stmts {
+    [Stmt::Return(ReturnStmt { arg: Some(arg), .. })] => Some(&**arg),
+    _ => None,
+  }
+}
+
+fn is_ident(expr: &Expr, name: &str) -> bool {
+  matches!(expr, Expr::Ident(ident) if ident.sym == name)
+}
+
+fn is_exports(expr: &Expr) -> bool {
+  match expr {
+    Expr::Ident(ident) => ident.sym == "exports",
+    Expr::Member(member) => is_module_exports(member),
+    _ => false,
+  }
+}
+
+fn is_module_exports(member: &MemberExpr) -> bool {
+  is_ident(&member.obj, "module") && member_prop_name(&member.prop).as_deref() == Some("exports")
+}
+
+/// Get `Button` from `exports.Button` or `module.exports.Button`.
+fn get_exports_property(member: &MemberExpr) -> Option<String> {
+  if is_exports(&member.obj) {
+    member_prop_name(&member.prop)
+  } else {
+    None
+  }
+}
+
+fn member_prop_name(prop: &MemberProp) -> Option<String> {
+  match prop {
+    MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+    MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+      Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+  match key {
+    PropName::Ident(ident) => Some(ident.sym.to_string()),
+    PropName::Str(s) => Some(s.value.to_string()),
+    _ => None,
+  }
+}
@@ -1,3 +1,5 @@
+#![feature(let_chains)]
+
 /**
  * Some code is modified based on
  * https://github.com/vercel/next.js/blob/fe9d74d411ef10fafe2f4a17eb2e30ee7c2ff731/packages/next-swc/crates/core/src/optimize_barrel.rs
@@ -6,277 +8,319 @@
  */
 use std::collections::HashMap;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use swc_core::{
-  common::DUMMY_SP,
-  ecma::{ast::*, utils::private_ident, visit::{fold_pass, Fold}},
+  common::{Span, Spanned},
+  ecma::ast::*,
 };
+use swc_keep_export::{decl_may_have_side_effects, may_have_side_effects};
+
+mod cjs;
+use cjs::analyze_cjs_barrel;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Config {
   pub wildcard: bool,
+  /// Allow local declarations in barrel files, they are collected as exports without `src`.
+  /// Only "mostly barrel" files are accepted, re-exports must not be outnumbered by local exports.
+  #[serde(default)]
+  pub partial: bool,
 }
 
-pub fn optimize_barrel(config: Config)-> impl Pass {
-  fold_pass(OptimizeBarrel {
-    wildcard: config.wildcard,
-  })
+/// A single export of the barrel file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportEntry {
+  /// Name exported by the barrel file.
+  pub name: String,
+  /// Module the export comes from, empty when it is declared locally.
+  pub src: String,
+  /// Name in the source module, `*` for namespace exports.
+  pub orig: String,
+  /// Span of the export in the barrel file, only meaningful for the parsed module.
+  #[serde(skip)]
+  pub span: Span,
 }
 
-#[derive(Debug, Default)]
-struct OptimizeBarrel {
-  wildcard: bool,
+/// Export meta information of a barrel file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BarrelAnalysis {
+  pub exports: Vec<ExportEntry>,
+  /// Sources of `export * from '...'` statements.
+  pub wildcard_exports: Vec<String>,
+  /// Directives at the top of the file, such as "use client".
+  pub directives: Vec<String>,
+  /// Whether the local statements of a partial barrel file have side effects,
+  /// such as `import './style.css'` or top level calls.
+  pub side_effects: bool,
 }
 
-impl Fold for OptimizeBarrel {
-  fn fold_module_items(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
-    // One pre-pass to find all the local idents that we are referencing, so we can
-    // handle the case of `import foo from 'a'; export { foo };` correctly.
+impl BarrelAnalysis {
+  pub fn is_client_entry(&self) -> bool {
+    self
+      .directives
+      .iter()
+      .any(|directive| directive.contains("use client"))
+  }
+}
 
-    // Map of "local ident" -> ("source module", "orig ident")
-    let mut local_idents = HashMap::new();
-    for item in &items {
-      if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
-        for spec in &import_decl.specifiers {
-          let src = import_decl.src.value.to_string();
-          match spec {
-            ImportSpecifier::Named(s) => {
-              local_idents.insert(
-                s.local.sym.to_string(),
-                (
-                  src.clone(),
-                  match &s.imported {
-                    Some(n) => match &n {
-                      ModuleExportName::Ident(n) => n.sym.to_string(),
-                      ModuleExportName::Str(n) => n.value.to_string(),
-                    },
-                    None => s.local.sym.to_string(),
-                  },
-                ),
-              );
-            }
-            ImportSpecifier::Namespace(s) => {
-              local_idents.insert(s.local.sym.to_string(), (src.clone(), "*".to_string()));
-            }
-            ImportSpecifier::Default(s) => {
-              local_idents.insert(
-                s.local.sym.to_string(),
-                (src.clone(), "default".to_string()),
-              );
-            }
+fn module_export_name(name: &ModuleExportName) -> String {
+  match name {
+    ModuleExportName::Ident(n) => n.sym.to_string(),
+    ModuleExportName::Str(n) => n.value.to_string(),
+  }
+}
+
+/// Analyze the exports of a module, returns `None` if the module is not a barrel file.
+pub fn analyze_barrel(module: &Module, config: &Config) -> Option<BarrelAnalysis> {
+  let items = &module.body;
+  if !items.iter().any(|item| matches!(item, ModuleItem::ModuleDecl(_))) {
+    return analyze_cjs_barrel(module, config);
+  }
+
+  // One pre-pass to find all the local idents that we are referencing, so we can
+  // handle the case of `import foo from 'a'; export { foo };` correctly.
+
+  // Map of "local ident" -> ("source module", "orig ident")
+  let mut local_idents = HashMap::new();
+  for item in items {
+    if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+      for spec in &import_decl.specifiers {
+        let src = import_decl.src.value.to_string();
+        match spec {
+          ImportSpecifier::Named(s) => {
+            local_idents.insert(
+              s.local.sym.to_string(),
+              (
+                src.clone(),
+                match &s.imported {
+                  Some(n) => module_export_name(n),
+                  None => s.local.sym.to_string(),
+                },
+              ),
+            );
+          }
+          ImportSpecifier::Namespace(s) => {
+            local_idents.insert(s.local.sym.to_string(), (src.clone(), "*".to_string()));
+          }
+          ImportSpecifier::Default(s) => {
+            local_idents.insert(
+              s.local.sym.to_string(),
+              (src.clone(), "default".to_string()),
+            );
           }
         }
       }
     }
+  }
 
-    // The second pass to rebuild the module items.
-    let mut new_items = vec![];
-
-    // Exported meta information.
-    let mut export_map = vec![];
-    let mut export_wildcards = vec![];
+  // Exported meta information.
+  let mut exports = vec![];
+  let mut push_export = |name: String, src: String, orig: String, span: Span| {
+    exports.push(ExportEntry {
+      name,
+      src,
+      orig,
+      span,
+    });
+  };
+  let mut wildcard_exports = vec![];
+  let mut directives = vec![];
+  let mut side_effects = false;
 
-    // We only apply this optimization to barrel files. Here we consider
-    // a barrel file to be a file that only exports from other modules.
+  // We only apply this optimization to barrel files. Here we consider
+  // a barrel file to be a file that only exports from other modules.
+  // In partial mode, local declarations are allowed as well.
+  let allow_local = config.wildcard || config.partial;
 
-    // Besides that, lit expressions are allowed as well ("use client", etc.).
-    let mut allowed_directives = true;
-    let mut directives = vec![];
+  // Besides that, lit expressions are allowed as well ("use client", etc.).
+  let mut allowed_directives = true;
 
-    let mut is_barrel = true;
-    for item in &items {
-      match item {
-        ModuleItem::ModuleDecl(decl) => {
-          allowed_directives = false;
-          match decl {
-            ModuleDecl::Import(_) => {}
-            // export { foo } from './foo';
-            ModuleDecl::ExportNamed(export_named) => {
-              for spec in &export_named.specifiers {
-                match spec {
-                  ExportSpecifier::Namespace(s) => {
-                    let name_str = match &s.name {
-                      ModuleExportName::Ident(n) => n.sym.to_string(),
-                      ModuleExportName::Str(n) => n.value.to_string(),
-                    };
-                    if let Some(src) = &export_named.src {
-                      export_map.push((name_str.clone(), src.value.to_string(), "*".to_string()));
-                    } else if self.wildcard {
-                      export_map.push((name_str.clone(), "".into(), "*".to_string()));
-                    } else {
-                      is_barrel = false;
-                      break;
-                    }
+  for item in items {
+    match item {
+      ModuleItem::ModuleDecl(decl) => {
+        allowed_directives = false;
+        match decl {
+          ModuleDecl::Import(import_decl) => {
+            // import './style.css';
+            if import_decl.specifiers.is_empty() && !import_decl.type_only {
+              side_effects = true;
+            }
+          }
+          // export { foo } from './foo';
+          ModuleDecl::ExportNamed(export_named) => {
+            for spec in &export_named.specifiers {
+              match spec {
+                ExportSpecifier::Namespace(s) => {
+                  let name_str = module_export_name(&s.name);
+                  if let Some(src) = &export_named.src {
+                    push_export(name_str, src.value.to_string(), "*".to_string(), s.span);
+                  } else if allow_local {
+                    push_export(name_str, "".into(), "*".to_string(), s.span);
+                  } else {
+                    return None;
                   }
-                  ExportSpecifier::Named(s) => {
-                    let orig_str = match &s.orig {
-                      ModuleExportName::Ident(n) => n.sym.to_string(),
-                      ModuleExportName::Str(n) => n.value.to_string(),
-                    };
-                    let name_str = match &s.exported {
-                      Some(n) => match &n {
-                        ModuleExportName::Ident(n) => n.sym.to_string(),
-                        ModuleExportName::Str(n) => n.value.to_string(),
-                      },
-                      None => orig_str.clone(),
-                    };
+                }
+                ExportSpecifier::Named(s) => {
+                  let orig_str = module_export_name(&s.orig);
+                  let name_str = match &s.exported {
+                    Some(n) => module_export_name(n),
+                    None => orig_str.clone(),
+                  };
 
-                    if let Some(src) = &export_named.src {
-                      export_map.push((name_str.clone(), src.value.to_string(), orig_str.clone()));
-                    } else if let Some((src, orig)) = local_idents.get(&orig_str) {
-                      export_map.push((name_str.clone(), src.clone(), orig.clone()));
-                    } else if self.wildcard {
-                      export_map.push((name_str.clone(), "".into(), orig_str.clone()));
-                    } else {
-                      is_barrel = false;
-                      break;
-                    }
+                  if let Some(src) = &export_named.src {
+                    push_export(name_str, src.value.to_string(), orig_str, s.span);
+                  } else if let Some((src, orig)) = local_idents.get(&orig_str) {
+                    push_export(name_str, src.clone(), orig.clone(), s.span);
+                  } else if allow_local {
+                    push_export(name_str, "".into(), orig_str, s.span);
+                  } else {
+                    return None;
                   }
-                  _ => {
-                    if !self.wildcard {
-                      is_barrel = false;
-                      break;
-                    }
+                }
+                // export v from './foo';
+                ExportSpecifier::Default(s) => {
+                  if let Some(src) = &export_named.src {
+                    push_export(
+                      s.exported.sym.to_string(),
+                      src.value.to_string(),
+                      "default".into(),
+                      s.exported.span,
+                    );
+                  } else if !allow_local {
+                    return None;
                   }
                 }
               }
             }
-            ModuleDecl::ExportAll(export_all) => {
-              export_wildcards.push(export_all.src.value.to_string());
+          }
+          ModuleDecl::ExportAll(export_all) => {
+            wildcard_exports.push(export_all.src.value.to_string());
+          }
+          ModuleDecl::ExportDecl(export_decl) => {
+            // Export declarations are not allowed in barrel files.
+            if !allow_local {
+              return None;
+            }
+            // export const store = createStore();
+            if decl_may_have_side_effects(&export_decl.decl) {
+              side_effects = true;
             }
-            ModuleDecl::ExportDecl(export_decl) => {
-              // Export declarations are not allowed in barrel files.
-              if !self.wildcard {
-                is_barrel = false;
-                break;
-              }
 
-              match &export_decl.decl {
-                Decl::Class(class) => {
-                  export_map.push((class.ident.sym.to_string(), "".into(), "".into()));
-                }
-                Decl::Fn(func) => {
-                  export_map.push((func.ident.sym.to_string(), "".into(), "".into()));
+            match &export_decl.decl {
+              Decl::Class(class) if !class.declare => {
+                push_export(
+                  class.ident.sym.to_string(),
+                  "".into(),
+                  "".into(),
+                  class.ident.span,
+                );
+              }
+              Decl::Fn(func) if !func.declare => {
+                push_export(
+                  func.ident.sym.to_string(),
+                  "".into(),
+                  "".into(),
+                  func.ident.span,
+                );
+              }
+              Decl::Var(var) if !var.declare => {
+                let ids = collect_idents_in_var_decls(&var.decls);
+                for id in ids {
+                  push_export(id, "".into(), "".into(), var.span);
                 }
-                Decl::Var(var) => {
-                  let ids = collect_idents_in_var_decls(&var.decls);
-                  for id in ids {
-                    export_map.push((id, "".into(), "".into()));
-                  }
+              }
+              Decl::TsEnum(ts_enum) if !ts_enum.declare => {
+                push_export(
+                  ts_enum.id.sym.to_string(),
+                  "".into(),
+                  "".into(),
+                  ts_enum.id.span,
+                );
+              }
+              Decl::TsModule(ts_module) if !ts_module.declare => {
+                if let TsModuleName::Ident(id) = &ts_module.id {
+                  push_export(id.sym.to_string(), "".into(), "".into(), id.span);
                 }
-                _ => {}
               }
+              // Types are removed after compilation.
+              _ => {}
             }
-            _ => {
-              if !self.wildcard {
-                // Other expressions are not allowed in barrel files.
-                is_barrel = false;
-                break;
-              }
+          }
+          ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) if config.partial => {
+            if let ModuleDecl::ExportDefaultExpr(export_default) = decl
+              && may_have_side_effects(&export_default.expr)
+            {
+              side_effects = true;
+            }
+            push_export("default".into(), "".into(), "default".into(), decl.span());
+          }
+          _ => {
+            if !allow_local {
+              // Other expressions are not allowed in barrel files.
+              return None;
             }
           }
         }
-        ModuleItem::Stmt(stmt) => match stmt {
-          Stmt::Expr(expr) => match &*expr.expr {
-            Expr::Lit(l) => {
-              if let Lit::Str(s) = l {
-                if allowed_directives && s.value.starts_with("use ") {
-                  directives.push(s.value.to_string());
-                }
-              } else {
-                allowed_directives = false;
+      }
+      ModuleItem::Stmt(stmt) => match stmt {
+        Stmt::Expr(expr) => match &*expr.expr {
+          Expr::Lit(l) => {
+            if let Lit::Str(s) = l {
+              if allowed_directives && s.value.starts_with("use ") {
+                directives.push(s.value.to_string());
               }
-            }
-            _ => {
+            } else {
               allowed_directives = false;
-              if !self.wildcard {
-                is_barrel = false;
-                break;
-              }
             }
-          },
+          }
           _ => {
             allowed_directives = false;
-            if !self.wildcard {
-              is_barrel = false;
-              break;
+            if !allow_local {
+              return None;
             }
+            side_effects = true;
           }
         },
-      }
+        // const store = createStore();
+        Stmt::Decl(decl) => {
+          allowed_directives = false;
+          if !allow_local {
+            return None;
+          }
+          if decl_may_have_side_effects(decl) {
+            side_effects = true;
+          }
+        }
+        _ => {
+          allowed_directives = false;
+          if !allow_local {
+            return None;
+          }
+          // Such as `if` and loops at the top level.
+          if !matches!(stmt, Stmt::Empty(_)) {
+            side_effects = true;
+          }
+        }
+      },
     }
+  }
 
-    // If the file is not a barrel file, we export nothing.
-    if !is_barrel {
-      new_items = vec![];
-    } else {
-      // Otherwise we export the meta information.
-      new_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-        span: DUMMY_SP,
-        decl: Decl::Var(Box::new(VarDecl {
-          span: DUMMY_SP,
-          kind: VarDeclKind::Const,
-          declare: false,
-          ctxt: Default::default(),
-          decls: vec![VarDeclarator {
-            span: DUMMY_SP,
-            name: Pat::Ident(BindingIdent {
-              id: private_ident!("__next_private_export_map__"),
-              type_ann: None,
-            }),
-            init: Some(Box::new(Expr::Lit(Lit::Str(Str {
-              span: DUMMY_SP,
-              value: serde_json::to_string(&export_map).unwrap().into(),
-              raw: None,
-            })))),
-            definite: false,
-          }],
-        })),
-      })));
-
-      // Push "export *" statements for each wildcard export.
-      for src in export_wildcards {
-        new_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll {
-          span: DUMMY_SP,
-          src: Box::new(Str {
-            span: DUMMY_SP,
-            value: format!("__barrel_optimize__?names=__PLACEHOLDER__!=!{}", src).into(),
-            raw: None,
-          }),
-          with: None,
-          type_only: false,
-        })));
-      }
-
-      // Push directives.
-      if !directives.is_empty() {
-        new_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-          span: DUMMY_SP,
-          decl: Decl::Var(Box::new(VarDecl {
-            span: DUMMY_SP,
-            kind: VarDeclKind::Const,
-            declare: false,
-            ctxt: Default::default(),
-            decls: vec![VarDeclarator {
-              span: DUMMY_SP,
-              name: Pat::Ident(BindingIdent {
-                id: private_ident!("__next_private_directive_list__"),
-                type_ann: None,
-              }),
-              init: Some(Box::new(Expr::Lit(Lit::Str(Str {
-                span: DUMMY_SP,
-                value: serde_json::to_string(&directives).unwrap().into(),
-                raw: None,
-              })))),
-              definite: false,
-            }],
-          })),
-        })));
-      }
+  // Partial barrel files must be mostly made of re-exports, otherwise splitting them
+  // into the residual module and the source modules does not pay off.
+  if config.partial && !config.wildcard {
+    let local_count = exports.iter().filter(|entry| entry.src.is_empty()).count();
+    let reexport_count = exports.len() - local_count + wildcard_exports.len();
+    if reexport_count == 0 || reexport_count < local_count {
+      return None;
     }
-
-    new_items
   }
+
+  Some(BarrelAnalysis {
+    exports,
+    wildcard_exports,
+    directives,
+    side_effects,
+  })
 }
 
 fn collect_idents_in_array_pat(elems: &[Option<Pat>]) -> Vec<String> {
@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use swc_core::common::{sync::Lrc, SourceMap};
+use swc_core::ecma::ast::EsVersion;
+use swc_core::ecma::parser::{parse_file_as_module, EsSyntax, Syntax, TsSyntax};
+use swc_optimize_barrel::{analyze_barrel, Config};
+use testing::NormalizedOutput;
+
+fn analyze(input: &Path, config: &Config) -> String {
+  let cm: Lrc<SourceMap> = Default::default();
+  let fm = cm.load_file(input).expect("failed to load fixture");
+  let syntax = if input.extension().is_some_and(|ext| ext == "ts") {
+    Syntax::Typescript(TsSyntax::default())
+  } else {
+    Syntax::Es(EsSyntax {
+      export_default_from: true,
+      ..Default::default()
+    })
+  };
+  let module = parse_file_as_module(
+    &fm,
+    syntax,
+    EsVersion::latest(),
+    None,
+    &mut vec![],
+  )
+  .expect("failed to parse fixture");
+  let analysis = analyze_barrel(&module, config).map(|analysis| {
+    json!({
+      "exports": analysis.exports,
+      "wildcardExports": analysis.wildcard_exports,
+      "directives": analysis.directives,
+      "sideEffects": analysis.side_effects,
+    })
+  });
+  format!("{}\n", serde_json::to_string_pretty(&analysis).unwrap())
+}
+
+fn test_analysis(input: PathBuf, config: Config) {
+  let output = input.with_file_name("output.json");
+  NormalizedOutput::from(analyze(&input, &config))
+    .compare_to_file(output)
+    .unwrap();
+}
+
+#[testing::fixture("tests/fixture/esm/**/input.js")]
+fn test_esm(input: PathBuf) {
+  test_analysis(
+    input,
+    Config {
+      wildcard: false,
+      partial: false,
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/cjs/**/input.js")]
+fn test_cjs(input: PathBuf) {
+  test_analysis(
+    input,
+    Config {
+      wildcard: false,
+      partial: false,
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/partial/**/input.[jt]s")]
+fn test_partial(input: PathBuf) {
+  test_analysis(
+    input,
+    Config {
+      wildcard: false,
+      partial: true,
+    },
+  );
+}
+
+// Barrel files reached through `export *` also export their local values.
+#[testing::fixture("tests/fixture/cjs_wildcard/**/input.js")]
+fn test_cjs_wildcard(input: PathBuf) {
+  test_analysis(
+    input,
+    Config {
+      wildcard: true,
+      partial: false,
+    },
+  );
+}
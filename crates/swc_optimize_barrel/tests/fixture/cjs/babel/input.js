@@ -0,0 +1,20 @@
+"use strict";
+
+Object.defineProperty(exports, "__esModule", {
+  value: true
+});
+Object.defineProperty(exports, "Button", {
+  enumerable: true,
+  get: function () {
+    return _button.default;
+  }
+});
+Object.defineProperty(exports, "Input", {
+  enumerable: true,
+  get: function () {
+    return _input.Input;
+  }
+});
+var _button = _interopRequireDefault(require("./button"));
+var _input = require("./input");
+function _interopRequireDefault(e) { return e && e.__esModule ? e : { default: e }; }
@@ -0,0 +1,10 @@
+'use strict';
+
+const Select = require('./select');
+
+module.exports = {
+  Button: require('./button'),
+  Input: require('./input').Input,
+  Select,
+  ...require('./icons'),
+};
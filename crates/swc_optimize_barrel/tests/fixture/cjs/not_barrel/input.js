@@ -0,0 +1,7 @@
+"use strict";
+
+exports.__esModule = true;
+exports.Button = require("./button").default;
+exports.format = function format(value) {
+  return String(value);
+};
@@ -0,0 +1,13 @@
+'use strict';
+
+const Select = require('./select');
+const format = (value) => String(value);
+
+module.exports = {
+  Button: require('./button'),
+  Input: require('./input').Input,
+  Select,
+  format,
+  version: '1.0.0',
+  ...require('./icons'),
+};
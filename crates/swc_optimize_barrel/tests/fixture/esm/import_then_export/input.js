@@ -0,0 +1,4 @@
+import foo from './foo';
+import * as bar from './bar';
+import { baz as qux } from './baz';
+export { foo, bar, qux as baz };
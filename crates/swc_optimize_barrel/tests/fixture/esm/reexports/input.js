@@ -0,0 +1,4 @@
+'use client';
+export { a, b as c } from './a';
+export * as ns from './ns';
+export * from './b';
@@ -0,0 +1,2 @@
+import './style.css';
+export { Button } from './button';
@@ -0,0 +1,5 @@
+export { Button } from './button';
+export const VERSION = '1.0';
+export function format(value) {
+  return String(value);
+}
@@ -0,0 +1,4 @@
+export { Button } from './button';
+export { Input } from './input';
+const sizes = ['small', `large`];
+export const getSize = (index) => sizes[index];
@@ -0,0 +1,4 @@
+export { Button } from './button';
+export { Input } from './input';
+const store = createStore();
+export const theme = createTheme(store);
@@ -0,0 +1,6 @@
+import './style.css';
+import { Input } from './input';
+export { Button } from './button';
+export { Input };
+export const VERSION = '1.0';
+console.log('loaded');
@@ -0,0 +1,11 @@
+export { Button } from './button';
+export * from './icons';
+export enum Size {
+  Small,
+  Large,
+}
+export namespace Utils {
+  export const noop = () => {};
+}
+export declare const VERSION: string;
+export interface Props {}
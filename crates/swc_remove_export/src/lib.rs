@@ -1,2 +1,3 @@
 mod transform;
+pub use swc_keep_export::{ExportPattern, ExportReport, ExportSummary, SideEffects};
 pub use transform::*;
@@ -4,6 +4,7 @@ use std::mem::take;
 
 use fxhash::FxHashSet;
 use rspack_error::Error;
+use crate::{ExportPattern, ExportReport, SideEffects};
 use swc_core::{common::{
   pass::{Repeat, Repeated},
   DUMMY_SP,
@@ -13,6 +14,18 @@ use swc_core::ecma::{
   visit::{noop_fold_type, Fold, FoldWith},
 };
 
+/// Replacement of a removed `default` export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DefaultExportStub {
+  /// `export default function() {}`
+  #[default]
+  EmptyFn,
+  /// A function throwing an error which names the removed export and `filename`.
+  Throw { filename: String },
+  /// `export default void 0`
+  Undefined,
+}
+
 /// State of the transforms. Shared by the analyzer and the transform.
 #[derive(Debug, Default)]
 struct State {
@@ -31,15 +44,26 @@ struct State {
   cur_declaring: FxHashSet<Id>,
 
   should_run_again: bool,
-  remove_exports: Vec<String>,
+  remove_exports: Vec<ExportPattern>,
+  report: ExportReport,
+  default_stub: DefaultExportStub,
+  side_effects: SideEffects,
 }
 
 impl State {
+  fn is_remove_export(&self, name: &str) -> bool {
+    self.remove_exports.iter().any(|pattern| pattern.matches(name))
+  }
+  fn should_remove_export(&mut self, name: &str) -> bool {
+    let remove = self.is_remove_export(name);
+    self.report.record_export(name, !remove);
+    remove
+  }
   fn should_remove_identifier(&mut self, i: &Ident) -> Result<bool, Error> {
-    Ok(self.remove_exports.contains(&String::from(&*i.sym)))
+    Ok(self.should_remove_export(&i.sym))
   }
   fn should_remove_default(&mut self) -> bool {
-    self.remove_exports.contains(&String::from("default"))
+    self.should_remove_export("default")
   }
 }
 
@@ -89,7 +113,7 @@ impl Fold for Analyzer<'_> {
 
   fn fold_export_named_specifier(&mut self, s: ExportNamedSpecifier) -> ExportNamedSpecifier {
     if let ModuleExportName::Ident(id) = &s.orig {
-      if !self.state.remove_exports.contains(&String::from(&*id.sym)) {
+      if !self.state.is_remove_export(&id.sym) {
         self.add_ref(id.to_id());
       }
     }
@@ -115,11 +139,7 @@ impl Fold for Analyzer<'_> {
           return s;
         }
         if let Pat::Ident(id) = &d.decls[0].name {
-          if self
-            .state
-            .remove_exports
-            .contains(&String::from(&*id.id.sym))
-          {
+          if self.state.should_remove_export(&id.id.sym) {
             self.in_data_fn = true;
             self.add_ref(id.to_id());
           }
@@ -312,6 +332,40 @@ impl RemoveExport {
       }),
     };
   }
+
+  fn create_default_stub(&mut self) -> Expr {
+    match self.state.default_stub.clone() {
+      DefaultExportStub::EmptyFn => Expr::Fn(self.create_empty_fn()),
+      DefaultExportStub::Throw { filename } => {
+        let message = format!(
+          "The default export of \"{filename}\" is removed from this build by `removeExport`, it can not be used here."
+        );
+        let throw_stmt = Stmt::Throw(ThrowStmt {
+          span: DUMMY_SP,
+          arg: Box::new(Expr::New(NewExpr {
+            span: DUMMY_SP,
+            callee: Box::new(Expr::Ident(Ident::new_no_ctxt("Error".into(), DUMMY_SP))),
+            args: Some(vec![ExprOrSpread {
+              spread: None,
+              expr: Box::new(Expr::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: message.into(),
+                raw: None,
+              }))),
+            }]),
+            type_args: None,
+            ctxt: Default::default(),
+          })),
+        });
+        let mut f = self.create_empty_fn();
+        if let Some(body) = &mut f.function.body {
+          body.stmts.push(throw_stmt);
+        }
+        Expr::Fn(f)
+      }
+      DefaultExportStub::Undefined => *Expr::undefined(DUMMY_SP),
+    }
+  }
 }
 
 impl Repeated for RemoveExport {
@@ -332,7 +386,7 @@ impl Fold for RemoveExport {
 
   // Remove import expression
   fn fold_import_decl(&mut self, mut i: ImportDecl) -> ImportDecl {
-    // Imports for side effects.
+    // Imports for side effects, only bare imports left by pruning are recorded.
     if i.specifiers.is_empty() {
       return i;
     }
@@ -342,6 +396,7 @@ impl Fold for RemoveExport {
       | ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
       | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
         if self.should_remove(local.to_id()) {
+          self.state.report.record_dropped_import(&i.src.value, &local.sym);
           self.state.should_run_again = true;
           false
         } else {
@@ -380,7 +435,11 @@ impl Fold for RemoveExport {
       let i = i.fold_with(self);
 
       if !is_for_side_effect && i.specifiers.is_empty() {
-        return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+        // Keep a bare import of modules with side effects, `import type` is never one.
+        if i.type_only || !self.state.side_effects.has_side_effects(&i.src.value) {
+          return ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }));
+        }
+        self.state.report.record_side_effect_import(&i.src.value);
       }
       return ModuleItem::ModuleDecl(ModuleDecl::Import(i));
     }
@@ -445,20 +504,24 @@ impl Fold for RemoveExport {
     n
   }
 
-  fn fold_default_decl(&mut self, d: DefaultDecl) -> DefaultDecl {
-    if self.state.should_remove_default() {
-      // Replace with an empty function
-      return DefaultDecl::Fn(self.create_empty_fn());
+  fn fold_module_decl(&mut self, d: ModuleDecl) -> ModuleDecl {
+    match d {
+      // Replaced with an expression, so the stub is not limited to functions and classes.
+      ModuleDecl::ExportDefaultDecl(e) if self.state.should_remove_default() => {
+        ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+          span: e.span,
+          expr: Box::new(self.create_default_stub()),
+        })
+      }
+      _ => d.fold_children_with(self),
     }
-    d
   }
 
   fn fold_export_default_expr(&mut self, n: ExportDefaultExpr) -> ExportDefaultExpr {
     if self.state.should_remove_default() {
-      // Replace with an empty function
       return ExportDefaultExpr {
         span: DUMMY_SP,
-        expr: Box::new(Expr::Fn(self.create_empty_fn())),
+        expr: Box::new(self.create_default_stub()),
       };
     }
     n
@@ -472,6 +535,7 @@ impl Fold for RemoveExport {
       match &mut p {
         Pat::Ident(name) => {
           if self.should_remove(name.id.to_id()) {
+            self.state.report.record_removed_declaration(&name.id.sym);
             self.state.should_run_again = true;
             return Pat::Invalid(Invalid { span: DUMMY_SP });
           }
@@ -538,6 +602,7 @@ impl Fold for RemoveExport {
     match s {
       Stmt::Decl(Decl::Fn(f)) => {
         if self.should_remove(f.ident.to_id()) {
+          self.state.report.record_removed_declaration(&f.ident.sym);
           self.mark_as_candidate(f.function);
           return Stmt::Empty(EmptyStmt { span: DUMMY_SP });
         }
@@ -583,10 +648,21 @@ impl Fold for RemoveExport {
   }
 }
 
-pub fn remove_export(exports: Vec<String>) -> impl Pass {
+/// Remove the exports matching `exports` and the code only they depend on, what is pruned is
+/// recorded to `report`. A removed `default` export is replaced with `default_stub`, and
+/// imports of modules with `side_effects` are kept as bare imports.
+pub fn remove_export(
+  exports: Vec<ExportPattern>,
+  report: ExportReport,
+  default_stub: DefaultExportStub,
+  side_effects: SideEffects,
+) -> impl Pass {
   fold_pass(Repeat::new(RemoveExport {
     state: State {
       remove_exports: exports,
+      report,
+      default_stub,
+      side_effects,
       ..Default::default()
     },
     in_lhs_of_var: false,
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_remove_export::{remove_export, DefaultExportStub, ExportReport, SideEffects};
+
+#[testing::fixture("tests/fixture/side_effect_imports/input.js")]
+fn test_side_effect_imports(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      remove_export(
+        vec!["dataLoader".into()],
+        ExportReport::default(),
+        DefaultExportStub::default(),
+        SideEffects::new(|src| src == "./polyfill" || src.ends_with(".css")),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/default_stub_empty_fn/input.js")]
+fn test_default_stub_empty_fn(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      remove_export(
+        vec!["default".into()],
+        ExportReport::default(),
+        DefaultExportStub::EmptyFn,
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/default_stub_throw/input.js")]
+fn test_default_stub_throw(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      remove_export(
+        vec!["default".into()],
+        ExportReport::default(),
+        DefaultExportStub::Throw {
+          filename: String::from("pages/index.js"),
+        },
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
+
+#[testing::fixture("tests/fixture/default_stub_undefined/input.js")]
+fn test_default_stub_undefined(input: PathBuf) {
+  let output = input.with_file_name("output.js");
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      remove_export(
+        vec!["default".into()],
+        ExportReport::default(),
+        DefaultExportStub::Undefined,
+        SideEffects::default(),
+      )
+    },
+    &input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+}
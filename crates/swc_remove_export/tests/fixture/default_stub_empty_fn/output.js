@@ -0,0 +1,4 @@
+export const pageConfig = () => ({
+  title: 'Home',
+});
+export default function() {}
@@ -0,0 +1,7 @@
+export const pageConfig = () => ({
+  title: 'Home',
+});
+
+export default function Page() {
+  return 'page';
+}
@@ -0,0 +1,6 @@
+export const pageConfig = () => ({
+  title: 'Home',
+});
+export default function() {
+  throw new Error('The default export of "pages/index.js" is removed from this build by `removeExport`, it can not be used here.');
+}
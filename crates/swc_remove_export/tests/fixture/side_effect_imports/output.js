@@ -0,0 +1,6 @@
+import './polyfill';
+import './index.module.css';
+import './setup';
+export const pageConfig = () => ({
+  title: 'Home',
+});
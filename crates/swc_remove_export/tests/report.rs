@@ -0,0 +1,35 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use swc_core::ecma::transforms::testing::{test_fixture, FixtureTestConfig};
+use swc_remove_export::{remove_export, DefaultExportStub, ExportReport, SideEffects};
+
+#[test]
+fn test_side_effect_imports_reported() {
+  let input = Path::new("tests/fixture/side_effect_imports/input.js");
+  let output = input.with_file_name("output.js");
+  let report = ExportReport::default();
+  test_fixture(
+    Default::default(),
+    &|_t| {
+      remove_export(
+        vec!["dataLoader".into()],
+        report.clone(),
+        DefaultExportStub::default(),
+        SideEffects::new(|src| src == "./polyfill" || src.ends_with(".css")),
+      )
+    },
+    input,
+    &output,
+    FixtureTestConfig {
+      ..Default::default()
+    },
+  );
+  let summary = report.summary();
+  // `import './setup'` is written as a bare import, so it is not pruned by the transform.
+  assert_eq!(
+    summary.side_effect_imports,
+    BTreeSet::from(["./index.module.css".to_string(), "./polyfill".to_string()])
+  );
+  assert!(summary.dropped_imports.contains_key("./request"));
+}